@@ -5,15 +5,20 @@ use core::{
     pin::Pin,
     sync::atomic::{AtomicBool, Ordering},
     task::{Context, Poll},
+    time::Duration,
 };
 
 use async_task::{Runnable, Task};
+use vexide_core::time::Instant;
 use waker_fn::waker_fn;
 
 use super::reactor::Reactor;
 
 pub(crate) static EXECUTOR: Executor = Executor::new();
 
+/// How often [`Executor::block_on`] checks the stack's guard region for an overflow.
+const STACK_CHECK_INTERVAL: Duration = Duration::from_millis(5);
+
 pub(crate) struct Executor {
     queue: RefCell<VecDeque<Runnable>>,
     reactor: RefCell<Reactor>,
@@ -75,8 +80,16 @@ impl Executor {
             move || woken.store(true, Ordering::Relaxed)
         });
         let mut cx = Context::from_waker(&waker);
+        let mut last_stack_check = Instant::now();
 
         loop {
+            // Catch stack overflows before they have a chance to corrupt the heap any further.
+            // Scanning the guard region isn't free, so only do so every few milliseconds.
+            if last_stack_check.elapsed() >= STACK_CHECK_INTERVAL {
+                vexide_core::stack::check();
+                last_stack_check = Instant::now();
+            }
+
             if woken.swap(false, Ordering::Relaxed) {
                 if let Poll::Ready(output) = Pin::new(&mut task).poll(&mut cx) {
                     return output;
@@ -1,12 +1,141 @@
 //! Asynchronous tasks.
+//!
+//! # Supervised Tasks
+//!
+//! A panic in any task will stop the entire program. For tasks that aren't critical to
+//! the robot's operation (such as an LED animation), [`spawn_supervised`] can be used to
+//! decide what happens when the task fails using a [`SupervisionPolicy`].
+//!
+//! Since vexide programs are compiled with `panic = "abort"`, panics can't be caught and
+//! recovered from. Instead, supervised tasks report failure by returning an [`Err`].
 
-use core::future::Future;
+use core::{fmt::Debug, future::Future, time::Duration};
 
 pub use async_task::{FallibleTask, Task};
+use vexide_core::{println, time::Instant};
 
-use crate::executor::EXECUTOR;
+use crate::{executor::EXECUTOR, time::sleep};
 
 /// Spawns a new async task that can be controlled with the returned task handle.
 pub fn spawn<T>(future: impl Future<Output = T> + 'static) -> Task<T> {
     EXECUTOR.spawn(future)
 }
+
+/// Determines what happens when a task spawned with [`spawn_supervised`] fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SupervisionPolicy {
+    /// Print the error and stop running the task.
+    Drop,
+
+    /// Print the error and restart the task after a delay.
+    ///
+    /// The delay starts at `initial_backoff` and doubles each time the task fails, up to
+    /// `max_backoff`. If the task runs for longer than `max_backoff` before failing, the
+    /// delay is reset to `initial_backoff`.
+    Restart {
+        /// The delay before the task is restarted the first time it fails.
+        initial_backoff: Duration,
+        /// The longest delay between restarts.
+        max_backoff: Duration,
+    },
+
+    /// Panic with the error, stopping the program.
+    Escalate,
+}
+
+impl SupervisionPolicy {
+    /// Restart the task with a backoff of 100ms, increasing up to 5 seconds.
+    pub const RESTART: Self = Self::Restart {
+        initial_backoff: Duration::from_millis(100),
+        max_backoff: Duration::from_secs(5),
+    };
+}
+
+/// Spawns a task that is supervised according to the given [`SupervisionPolicy`].
+///
+/// `task` is called to create the task's future. If the future returns an [`Err`], the
+/// error is handled according to `policy`, which may call `task` again to restart it. The
+/// supervised task completes once the future returns [`Ok`] or the policy stops it.
+///
+/// `name` is used to identify the task when printing errors.
+///
+/// # Panics
+///
+/// - Panics if the task fails and `policy` is [`SupervisionPolicy::Escalate`].
+///
+/// # Examples
+///
+/// ```
+/// use core::{cell::RefCell, time::Duration};
+///
+/// use alloc::rc::Rc;
+/// use vexide::{
+///     devices::{adi::addrled::AddrLedError, rgb::Rgb},
+///     prelude::*,
+///     task::{spawn_supervised, SupervisionPolicy},
+/// };
+///
+/// async fn animate(leds: Rc<RefCell<AdiAddrLed>>) -> Result<(), AddrLedError> {
+///     loop {
+///         leds.borrow_mut().set_all(Rgb::new(255, 0, 0))?;
+///         sleep(Duration::from_millis(500)).await;
+///         leds.borrow_mut().set_all(Rgb::new(0, 0, 255))?;
+///         sleep(Duration::from_millis(500)).await;
+///     }
+/// }
+///
+/// #[vexide::main]
+/// async fn main(peripherals: Peripherals) {
+///     let leds = Rc::new(RefCell::new(AdiAddrLed::new(peripherals.adi_a, 30).unwrap()));
+///
+///     // If the LED strip is unplugged, keep trying to animate it instead of panicking.
+///     spawn_supervised("leds", SupervisionPolicy::RESTART, move || animate(leds.clone()))
+///         .detach();
+/// }
+/// ```
+pub fn spawn_supervised<F, Fut, E>(
+    name: &'static str,
+    policy: SupervisionPolicy,
+    mut task: F,
+) -> Task<()>
+where
+    F: FnMut() -> Fut + 'static,
+    Fut: Future<Output = Result<(), E>> + 'static,
+    E: Debug,
+{
+    spawn(async move {
+        let mut backoff = None;
+
+        loop {
+            let start = Instant::now();
+            let Err(error) = task().await else {
+                return;
+            };
+
+            match policy {
+                SupervisionPolicy::Drop => {
+                    println!("task `{name}` failed: {error:?}");
+                    return;
+                }
+                SupervisionPolicy::Restart {
+                    initial_backoff,
+                    max_backoff,
+                } => {
+                    let delay = match backoff {
+                        Some(previous) if start.elapsed() <= max_backoff => {
+                            max_backoff.min(previous * 2)
+                        }
+                        _ => initial_backoff,
+                    };
+                    backoff = Some(delay);
+
+                    println!("task `{name}` failed: {error:?} (restarting in {delay:?})");
+                    sleep(delay).await;
+                }
+                SupervisionPolicy::Escalate => {
+                    panic!("task `{name}` failed: {error:?}");
+                }
+            }
+        }
+    })
+}
@@ -5,13 +5,177 @@
 //! [`claim`] must be called before any heap allocations are made.
 //! This is done automatically for you in the `vexide-startup` crate, so you should not need to call
 //! it yourself unless you are writing your own startup routine implementation.
+//!
+//! # Heap Statistics
+//!
+//! The current state of the heap can be inspected using [`heap_stats`], which can be helpful
+//! when tracking down memory leaks or excessive memory usage.
+//!
+//! If an allocation fails because the heap is out of memory, the allocator's OOM hook is
+//! called with the failed allocation's [`Layout`] and the heap statistics at the time of
+//! the failure before the program panics. By default, this prints the information to
+//! stdout, but a custom hook can be set using [`set_oom_hook`].
+
+use core::{
+    alloc::{GlobalAlloc, Layout},
+    ptr::{self, NonNull},
+    sync::atomic::{AtomicPtr, AtomicUsize, Ordering},
+};
 
-use talc::{ErrOnOom, Span, Talc, Talck};
+use talc::{OomHandler, Span, Talc, Talck};
 
-use crate::sync::RawMutex;
+use crate::{
+    io::{stdout, Write},
+    sync::RawMutex,
+};
 
 #[global_allocator]
-static ALLOCATOR: Talck<RawMutex, ErrOnOom> = Talc::new(ErrOnOom).lock();
+static ALLOCATOR: Allocator = Allocator(Talc::new(OomReporter).lock());
+
+/// The highest number of bytes that have been allocated at once.
+static PEAK_ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+/// The hook set by [`set_oom_hook`], or null if the default hook should be used.
+///
+/// This is stored as a raw pointer rather than behind a lock so that setting the hook can
+/// never fail, even if it happens while the OOM handler is running.
+static OOM_HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Statistics about the usage of the heap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeapStats {
+    /// The number of bytes currently allocated.
+    pub allocated_bytes: usize,
+    /// The number of bytes available for allocation.
+    ///
+    /// Because the free space may be split into multiple fragments, an allocation of this
+    /// size is not guaranteed to succeed.
+    pub free_bytes: usize,
+    /// The highest number of bytes that have been allocated at once since the program started.
+    pub peak_allocated_bytes: usize,
+    /// The number of allocations that are currently live.
+    pub allocation_count: usize,
+    /// The number of allocations that have been made since the program started.
+    pub total_allocation_count: u64,
+    /// The number of separate regions that the free space is split into.
+    ///
+    /// A high number of fragments relative to the number of live allocations indicates that
+    /// the heap is fragmented.
+    pub fragment_count: usize,
+}
+
+impl HeapStats {
+    fn new(talc: &Talc<OomReporter>) -> Self {
+        let counters = talc.get_counters();
+
+        Self {
+            allocated_bytes: counters.allocated_bytes,
+            free_bytes: counters.available_bytes,
+            peak_allocated_bytes: PEAK_ALLOCATED_BYTES
+                .load(Ordering::Relaxed)
+                .max(counters.allocated_bytes),
+            allocation_count: counters.allocation_count,
+            total_allocation_count: counters.total_allocation_count,
+            fragment_count: counters.fragment_count,
+        }
+    }
+}
+
+/// A wrapper around the `talc` allocator that keeps track of peak heap usage.
+struct Allocator(Talck<RawMutex, OomReporter>);
+
+impl Allocator {
+    /// Runs an allocation with the heap locked, recording the peak heap usage under the same
+    /// lock if it succeeds.
+    fn allocate_with(
+        &self,
+        allocate: impl FnOnce(&mut Talc<OomReporter>) -> Result<NonNull<u8>, ()>,
+    ) -> *mut u8 {
+        let mut talc = self.0.lock();
+
+        match allocate(&mut talc) {
+            Ok(ptr) => {
+                let allocated_bytes = talc.get_counters().allocated_bytes;
+                PEAK_ALLOCATED_BYTES.fetch_max(allocated_bytes, Ordering::Relaxed);
+                ptr.as_ptr()
+            }
+            Err(()) => ptr::null_mut(),
+        }
+    }
+}
+
+unsafe impl GlobalAlloc for Allocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.allocate_with(|talc| unsafe { talc.malloc(layout) })
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { self.alloc(layout) };
+        if !ptr.is_null() {
+            // Zeroing can be done after the heap has been unlocked.
+            unsafe { ptr.write_bytes(0, layout.size()) };
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { self.0.lock().free(NonNull::new_unchecked(ptr), layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let ptr = unsafe { NonNull::new_unchecked(ptr) };
+
+        if new_size > layout.size() {
+            self.allocate_with(|talc| unsafe { talc.grow(ptr, layout, new_size) })
+        } else {
+            if new_size < layout.size() {
+                unsafe { self.0.lock().shrink(ptr, layout, new_size) };
+            }
+            ptr.as_ptr()
+        }
+    }
+}
+
+/// An out-of-memory handler that runs the OOM hook before failing the allocation.
+struct OomReporter;
+
+impl OomHandler for OomReporter {
+    fn handle_oom(talc: &mut Talc<Self>, layout: Layout) -> Result<(), ()> {
+        let stats = HeapStats::new(talc);
+
+        oom_hook()(layout, &stats);
+
+        // Fail the allocation, which will cause the program to panic.
+        Err(())
+    }
+}
+
+/// Returns the current OOM hook.
+fn oom_hook() -> fn(Layout, &HeapStats) {
+    let hook = OOM_HOOK.load(Ordering::Acquire);
+
+    if hook.is_null() {
+        default_oom_hook
+    } else {
+        // SAFETY: The only non-null values stored in `OOM_HOOK` are hooks passed to
+        // `set_oom_hook`, which have this type.
+        unsafe { core::mem::transmute::<*mut (), fn(Layout, &HeapStats)>(hook) }
+    }
+}
+
+/// The default OOM hook, which prints the failed allocation and heap statistics to stdout.
+fn default_oom_hook(layout: Layout, stats: &HeapStats) {
+    // `println!` allocates, so we have to write to stdout directly.
+    if let Some(mut stdout) = stdout().try_lock() {
+        _ = writeln!(
+            stdout,
+            "out of memory: failed to allocate {} bytes (align {})",
+            layout.size(),
+            layout.align()
+        );
+        _ = writeln!(stdout, "{stats:#?}");
+    }
+}
 
 /// Claims a region of memory as heap space.
 ///
@@ -28,6 +192,45 @@ static ALLOCATOR: Talck<RawMutex, ErrOnOom> = Talc::new(ErrOnOom).lock();
 pub unsafe fn claim(start: *mut u8, end: *mut u8) {
     //SAFETY: User must ensure that this function is only called once.
     unsafe {
-        ALLOCATOR.lock().claim(Span::new(start, end)).unwrap();
+        ALLOCATOR.0.lock().claim(Span::new(start, end)).unwrap();
     }
 }
+
+/// Returns statistics about the current usage of the heap.
+///
+/// # Examples
+///
+/// ```
+/// use vexide::allocator::heap_stats;
+///
+/// let stats = heap_stats();
+/// println!(
+///     "{} bytes allocated ({} bytes at peak), {} bytes free",
+///     stats.allocated_bytes, stats.peak_allocated_bytes, stats.free_bytes
+/// );
+/// ```
+#[must_use]
+pub fn heap_stats() -> HeapStats {
+    HeapStats::new(&ALLOCATOR.0.lock())
+}
+
+/// Sets the hook that is called when an allocation fails because the heap is out of memory.
+///
+/// The hook is called with the [`Layout`] of the failed allocation and the heap statistics
+/// at the time of the failure. After the hook returns, the program will panic.
+///
+/// The heap is locked while the hook runs, so **the hook must not allocate**. Doing so will
+/// cause the program to hang.
+///
+/// # Examples
+///
+/// ```
+/// use vexide::allocator::set_oom_hook;
+///
+/// set_oom_hook(|layout, stats| {
+///     // Write the layout and stats somewhere without allocating...
+/// });
+/// ```
+pub fn set_oom_hook(hook: fn(Layout, &HeapStats)) {
+    OOM_HOOK.store(hook as *mut (), Ordering::Release);
+}
@@ -13,6 +13,12 @@
 //!
 //! Additionally, backtraces will be unsupported if vexide is compiled without
 //! the `unwind` feature.
+//!
+//! # Symbolication
+//!
+//! When the `symbolication` feature is enabled, a table of function names can be
+//! embedded into the program, allowing backtraces to be displayed with function
+//! names directly on the brain. See [`symbol_name`] for more information.
 
 use alloc::vec::Vec;
 use core::{ffi::c_void, fmt::Display};
@@ -54,6 +60,9 @@ use vex_libunwind::{registers, UnwindContext, UnwindCursor, UnwindError};
 ///
 /// main at /path/to/project/src/main.rs:21:9
 /// ```
+///
+/// Alternatively, if the `symbolication` feature is enabled, function names will be
+/// looked up on the brain and included when the backtrace is displayed.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Backtrace {
     /// The instruction pointers of each frame in the backtrace.
@@ -120,13 +129,86 @@ impl Backtrace {
 impl Display for Backtrace {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         writeln!(f, "stack backtrace:")?;
+
+        let mut symbolicated = !self.frames.is_empty();
         for (i, frame) in self.frames.iter().enumerate() {
-            writeln!(f, "{i:>3}: {frame:?}")?;
+            if let Some(name) = symbol_name(*frame) {
+                writeln!(f, "{i:>3}: {frame:?} - {name}")?;
+            } else {
+                writeln!(f, "{i:>3}: {frame:?}")?;
+                symbolicated = false;
+            }
+        }
+
+        if !symbolicated {
+            write!(
+                f,
+                "note: Use a symbolizer to convert stack frames to human-readable function names."
+            )?;
         }
-        write!(
-            f,
-            "note: Use a symbolizer to convert stack frames to human-readable function names."
-        )?;
+
         Ok(())
     }
 }
+
+/// The number of bytes reserved in the program for the symbol table.
+///
+/// Programs with a large number of functions may need more space than this, in which
+/// case the table will fail to be generated.
+#[cfg(all(target_vendor = "vex", feature = "symbolication"))]
+pub const SYMBOL_TABLE_CAPACITY: usize = 128 * 1024;
+
+/// Space reserved for the symbol table, filled in after linking.
+///
+/// The table must be read through the linker-defined section bounds rather than through
+/// this static, since the compiler would otherwise assume that it is still all zeroes.
+#[cfg(all(target_vendor = "vex", feature = "symbolication"))]
+#[link_section = ".vexide_symbols"]
+#[used]
+static SYMBOL_TABLE: [u8; SYMBOL_TABLE_CAPACITY] = [0; SYMBOL_TABLE_CAPACITY];
+
+#[cfg(all(target_vendor = "vex", feature = "symbolication"))]
+fn symbol_table() -> Option<vexide_symbols::SymbolTable<'static>> {
+    extern "C" {
+        static __vexide_symbols_start: u8;
+        static __vexide_symbols_end: u8;
+    }
+
+    let data = unsafe {
+        let start = core::ptr::addr_of!(__vexide_symbols_start);
+        let end = core::ptr::addr_of!(__vexide_symbols_end);
+        core::slice::from_raw_parts(start, end as usize - start as usize)
+    };
+
+    vexide_symbols::SymbolTable::parse(data)
+}
+
+/// Returns the name of the function containing the given instruction pointer.
+///
+/// Function names are only available when the `symbolication` feature is enabled and the
+/// program's symbol table has been embedded after linking by running the `vexide-symbols`
+/// command on its ELF file (see the `vexide-symbols` crate for how to set this up as a Cargo
+/// runner). Otherwise, this always returns `None`.
+///
+/// # Examples
+///
+/// ```
+/// use vexide::backtrace::{symbol_name, Backtrace};
+///
+/// let backtrace = Backtrace::capture();
+/// for frame in backtrace.frames {
+///     println!("{frame:?}: {}", symbol_name(frame).unwrap_or("<unknown>"));
+/// }
+/// ```
+#[must_use]
+#[allow(clippy::missing_const_for_fn)]
+pub fn symbol_name(instruction_pointer: *const c_void) -> Option<&'static str> {
+    #[cfg(all(target_vendor = "vex", feature = "symbolication"))]
+    return symbol_table()?.lookup(instruction_pointer as u32);
+
+    #[cfg(not(all(target_vendor = "vex", feature = "symbolication")))]
+    {
+        _ = instruction_pointer;
+        None
+    }
+}
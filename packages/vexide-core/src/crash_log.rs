@@ -0,0 +1,269 @@
+//! In-memory crash log.
+//!
+//! This module provides a small, fixed-size ring buffer of recent log output that
+//! survives into the panic handler. When a program panics, the panic message and
+//! backtrace only tell you *where* things went wrong, not what the program was doing
+//! leading up to it. The crash log keeps the last [`CAPACITY`] bytes of output around
+//! so that the panic handler can dump them alongside the panic message.
+//!
+//! Everything written through [`print!`](crate::io::print) and [`println!`](crate::io::println)
+//! is automatically recorded. Messages that should only ever appear in a crash report
+//! can be recorded without printing them using [`record`].
+//!
+//! # Allocation
+//!
+//! The crash log is backed by a statically allocated buffer and never touches the heap,
+//! either when recording or when dumping its contents. This means that it stays usable
+//! from the panic handler even if the program panicked because it ran out of memory.
+//!
+//! # Examples
+//!
+//! ```
+//! use vexide::crash_log;
+//!
+//! crash_log::record(format_args!("autonomous route {} selected", 3));
+//!
+//! // Later, possibly from a panic hook:
+//! crash_log::dump(|chunk| {
+//!     // Write `chunk` somewhere...
+//! });
+//! ```
+
+use core::{ffi::CStr, fmt};
+
+use crate::sync::Mutex;
+
+/// The number of bytes of recent output retained by the crash log.
+pub const CAPACITY: usize = 4096;
+
+/// The file on the SD card that [`save`] writes the crash log to.
+pub const CRASH_LOG_PATH: &CStr = c"crash_log.txt";
+
+static CRASH_LOG: Mutex<RingBuffer> = Mutex::new(RingBuffer::new());
+
+/// A fixed-capacity byte ring buffer that overwrites its oldest contents when full.
+struct RingBuffer {
+    buf: [u8; CAPACITY],
+    /// Index that the next byte will be written to.
+    head: usize,
+    /// Number of valid bytes in the buffer.
+    len: usize,
+}
+
+impl RingBuffer {
+    const fn new() -> Self {
+        Self {
+            buf: [0; CAPACITY],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, mut bytes: &[u8]) {
+        // Only the tail end of a write larger than the buffer would survive anyways.
+        if bytes.len() > CAPACITY {
+            bytes = &bytes[bytes.len() - CAPACITY..];
+        }
+
+        for &byte in bytes {
+            self.buf[self.head] = byte;
+            self.head = (self.head + 1) % CAPACITY;
+        }
+
+        self.len = (self.len + bytes.len()).min(CAPACITY);
+    }
+
+    /// Returns the contents of the buffer as two slices, oldest data first.
+    fn as_slices(&self) -> (&[u8], &[u8]) {
+        if self.len < CAPACITY {
+            (&self.buf[..self.head], &[])
+        } else {
+            let (newer, older) = self.buf.split_at(self.head);
+
+            // The oldest line was likely partially overwritten, so skip to the start
+            // of the next full line if there is one.
+            let older = match older.iter().position(|&b| b == b'\n') {
+                Some(newline) => &older[newline + 1..],
+                None => older,
+            };
+
+            (older, newer)
+        }
+    }
+}
+
+impl fmt::Write for RingBuffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// Records a message to the crash log without printing it, followed by a newline.
+///
+/// The message is formatted directly into the crash log's buffer without allocating.
+/// If the crash log is currently in use (for example, if a `Display` implementation
+/// passed to this function records a message of its own), the message is dropped.
+///
+/// # Examples
+///
+/// ```
+/// use vexide::crash_log;
+///
+/// crash_log::record(format_args!("lift target: {}", 42));
+/// ```
+pub fn record(args: fmt::Arguments<'_>) {
+    use core::fmt::Write;
+
+    if let Some(mut log) = CRASH_LOG.try_lock() {
+        _ = log.write_fmt(args);
+        log.push(b"\n");
+    }
+}
+
+/// Appends raw bytes to the crash log.
+///
+/// This is used internally to record everything written to stdout.
+pub(crate) fn record_bytes(bytes: &[u8]) {
+    if let Some(mut log) = CRASH_LOG.try_lock() {
+        log.push(bytes);
+    }
+}
+
+/// Clears the contents of the crash log.
+pub fn clear() {
+    if let Some(mut log) = CRASH_LOG.try_lock() {
+        *log = RingBuffer::new();
+    }
+}
+
+/// Passes the contents of the crash log to `f` in one or two chunks, oldest data first.
+///
+/// No allocations are performed, making this safe to call from a panic hook even if the
+/// heap has been exhausted.
+///
+/// Returns `false` if the crash log could not be accessed because it was already in use.
+pub fn dump(mut f: impl FnMut(&[u8])) -> bool {
+    let Some(log) = CRASH_LOG.try_lock() else {
+        return false;
+    };
+
+    let (older, newer) = log.as_slices();
+    for chunk in [older, newer] {
+        if !chunk.is_empty() {
+            f(chunk);
+        }
+    }
+
+    true
+}
+
+/// Writes the contents of the crash log to [`CRASH_LOG_PATH`] on the SD card,
+/// replacing the file if it exists.
+///
+/// Unlike the [`fs`](crate::fs) module, this function talks to the filesystem
+/// directly and does not allocate.
+///
+/// Returns `false` if the file could not be opened.
+#[must_use]
+pub fn save() -> bool {
+    unsafe {
+        if vex_sdk::vexFileMountSD() != vex_sdk::FRESULT::FR_OK {
+            return false;
+        }
+
+        let fd = vex_sdk::vexFileOpenCreate(CRASH_LOG_PATH.as_ptr());
+        if fd.is_null() {
+            return false;
+        }
+
+        dump(|chunk| {
+            vex_sdk::vexFileWrite(chunk.as_ptr().cast_mut().cast(), 1, chunk.len() as _, fd);
+        });
+
+        vex_sdk::vexFileClose(fd);
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Returns the contents of the buffer, oldest data first.
+    fn contents(buffer: &RingBuffer) -> ([u8; CAPACITY], usize) {
+        let (older, newer) = buffer.as_slices();
+        let mut contents = [0; CAPACITY];
+        contents[..older.len()].copy_from_slice(older);
+        contents[older.len()..older.len() + newer.len()].copy_from_slice(newer);
+        (contents, older.len() + newer.len())
+    }
+
+    #[test]
+    fn push_before_full() {
+        let mut buffer = RingBuffer::new();
+        buffer.push(b"hello ");
+        buffer.push(b"world\n");
+
+        let (contents, len) = contents(&buffer);
+        assert_eq!(&contents[..len], b"hello world\n");
+    }
+
+    #[test]
+    fn wraparound_keeps_newest_data() {
+        let mut buffer = RingBuffer::new();
+        buffer.push(&[b'a'; CAPACITY - 4]);
+        buffer.push(b"\n12345678\n");
+
+        let (older, newer) = buffer.as_slices();
+        // The partially overwritten line of `a`s is skipped entirely.
+        assert_eq!(older, b"123");
+        assert_eq!(newer, b"45678\n");
+    }
+
+    #[test]
+    fn partial_line_is_skipped() {
+        let mut buffer = RingBuffer::new();
+        for line in 0..CAPACITY / 8 {
+            buffer.push(&[b'0' + (line % 10) as u8; 7]);
+            buffer.push(b"\n");
+        }
+        // Overwrite the first line and half of the second.
+        buffer.push(b"abcdefghijkl");
+
+        let (contents, len) = contents(&buffer);
+        let contents = &contents[..len];
+        assert!(contents.starts_with(b"2222222\n"));
+        assert!(contents.ends_with(b"\nabcdefghijkl"));
+        assert_eq!(len, CAPACITY - 4);
+    }
+
+    #[test]
+    fn no_newline_keeps_everything() {
+        let mut buffer = RingBuffer::new();
+        buffer.push(&[b'a'; CAPACITY]);
+        buffer.push(b"bc");
+
+        let (contents, len) = contents(&buffer);
+        assert_eq!(len, CAPACITY);
+        assert_eq!(&contents[CAPACITY - 2..], b"bc");
+    }
+
+    #[test]
+    fn oversized_write_is_truncated() {
+        let mut buffer = RingBuffer::new();
+        buffer.push(b"discarded\n");
+
+        let mut bytes = [b'x'; CAPACITY + 100];
+        bytes[99] = b'\n';
+        bytes[100] = b'y';
+        buffer.push(&bytes);
+
+        // Only the last `CAPACITY` bytes are kept, so the newline before them is gone too.
+        let (contents, len) = contents(&buffer);
+        assert_eq!(len, CAPACITY);
+        assert_eq!(contents[0], b'y');
+        assert!(contents[1..].iter().all(|&byte| byte == b'x'));
+    }
+}
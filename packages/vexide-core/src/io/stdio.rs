@@ -182,6 +182,10 @@ pub fn __print(args: core::fmt::Arguments<'_>) {
     // Format the arguments into a byte buffer before printing them.
     // This lets us calculate if the bytes will overflow the buffer before printing them.
     let formatted_bytes = format!("{args}").into_bytes();
+
+    #[cfg(feature = "crash_log")]
+    crate::crash_log::record_bytes(&formatted_bytes);
+
     let remaining_bytes_in_buffer = unsafe { vexSerialWriteFree(STDIO_CHANNEL) as usize };
 
     // Write all of our data in chunks the size of the outgoing serial buffer.
@@ -4,9 +4,11 @@
 //! Included in this crate:
 //! - Global allocator: [`allocator`]
 //! - Competition state handling: [`competition`]
+//! - Recent output for crash reports: [`crash_log`]
 //! - Serial terminal printing: [`io`]
 //! - No-std [`Instant`](time::Instant)s: [`time`]
 //! - Synchronization primitives: [`sync`]
+//! - Stack usage monitoring: [`stack`]
 //! - Program control: [`program`]
 
 #![no_std]
@@ -18,11 +20,14 @@ extern crate alloc;
 pub mod allocator;
 pub mod backtrace;
 pub mod competition;
+#[cfg(feature = "crash_log")]
+pub mod crash_log;
 pub mod float;
 pub mod fs;
 pub mod io;
 pub mod os;
 pub mod path;
 pub mod program;
+pub mod stack;
 pub mod sync;
 pub mod time;
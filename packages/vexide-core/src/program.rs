@@ -37,6 +37,39 @@ impl<T: Termination, E: Debug> Termination for Result<T, E> {
 
 const FLUSH_TIMEOUT: Duration = Duration::from_millis(15);
 
+/// Calls each function in the `.fini_array` section (C/C++ global destructors) in reverse
+/// order.
+///
+/// This only runs the destructors the first time it is called, since [`exit`] may be
+/// reached more than once (for example, if a destructor panics).
+#[cfg(all(target_vendor = "vex", feature = "global_constructors"))]
+fn run_global_destructors() {
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    static FINISHED: AtomicBool = AtomicBool::new(false);
+
+    // These symbols are defined in vexide's linkerscript (link/v5.ld).
+    unsafe extern "C" {
+        static __fini_array_start: Option<unsafe extern "C" fn()>;
+        static __fini_array_end: Option<unsafe extern "C" fn()>;
+    }
+
+    if FINISHED.swap(true, Ordering::Relaxed) {
+        return;
+    }
+
+    let start = &raw const __fini_array_start;
+    let mut entry = &raw const __fini_array_end;
+    while entry > start {
+        unsafe {
+            entry = entry.sub(1);
+            if let Some(destructor) = *entry {
+                destructor();
+            }
+        }
+    }
+}
+
 /// Exits the program using `vexSystemExitRequest`.
 ///
 /// This function will block up to 15mS to allow the serial buffer to flush, then either exit the program or
@@ -54,7 +87,13 @@ const FLUSH_TIMEOUT: Duration = Duration::from_millis(15);
 ///    Ok(())
 /// }
 /// ```
+///
+/// If the `global_constructors` feature is enabled, C/C++ global destructors (the `.fini_array`
+/// section) are run before the program exits.
 pub fn exit() -> ! {
+    #[cfg(all(target_vendor = "vex", feature = "global_constructors"))]
+    run_global_destructors();
+
     let exit_time = Instant::now();
 
     unsafe {
@@ -0,0 +1,157 @@
+//! Stack usage monitoring.
+//!
+//! User programs run on a fixed-size stack placed directly above the heap. Since the V5
+//! Brain has no memory protection for user programs, overflowing the stack silently
+//! overwrites the end of the heap rather than faulting, which usually shows up later as
+//! confusing crashes or corrupted data.
+//!
+//! To catch this, the startup routine paints the unused part of the stack with a known
+//! pattern before any user code runs. This allows for:
+//!
+//! - Measuring the most stack space the program has used so far: [`high_water_mark`]
+//! - Detecting when the stack has grown into the guard region at its bottom: [`is_overflowed`]
+//!
+//! The async executor checks the guard region every few milliseconds, and the panic
+//! handler notes an overflow when reporting a panic.
+//!
+//! Stack usage can only be measured on the V5 Brain. On other targets, all functions in
+//! this module act as if the stack is empty.
+
+/// The word that the unused part of the stack is painted with.
+#[cfg(target_vendor = "vex")]
+const PAINT: u32 = 0xDEAD_BEEF;
+
+/// The number of bytes at the bottom of the stack that are reserved as a guard region.
+///
+/// The stack is considered to have overflowed once any of these bytes have been written to.
+pub const GUARD_SIZE: usize = 1024;
+
+/// The number of bytes below the current stack pointer that are left unpainted by [`paint`],
+/// leaving room for the stack frames of the painting routine itself.
+#[cfg(target_vendor = "vex")]
+const PAINT_MARGIN: usize = 512;
+
+/// Returns the bottom (lowest address) and top (highest address) of the stack.
+#[cfg(target_vendor = "vex")]
+fn bounds() -> (*mut u32, *mut u32) {
+    // These symbols are defined in vexide's linkerscript (link/v5.ld).
+    unsafe extern "C" {
+        static mut __stack_bottom: u32;
+        static mut __stack_top: u32;
+    }
+
+    (&raw mut __stack_bottom, &raw mut __stack_top)
+}
+
+/// Paints the unused part of the stack so that its usage can be measured.
+///
+/// This is done automatically for you in the `vexide-startup` crate, so you should not
+/// need to call it yourself unless you are writing your own startup routine implementation.
+///
+/// # Safety
+///
+/// Must be called at the start of program execution while running on the stack defined in
+/// vexide's linkerscript, and before any stack memory below the current stack pointer is
+/// expected to hold data.
+#[allow(clippy::missing_const_for_fn)]
+pub unsafe fn paint() {
+    #[cfg(target_vendor = "vex")]
+    unsafe {
+        let sp: usize;
+        core::arch::asm!("mov {}, sp", out(reg) sp, options(nomem, nostack, preserves_flags));
+
+        let (bottom, _) = bounds();
+        let end = (sp - PAINT_MARGIN) as *mut u32;
+
+        let mut word = bottom;
+        while word < end {
+            word.write_volatile(PAINT);
+            word = word.add(1);
+        }
+    }
+}
+
+/// Returns the total size of the stack in bytes.
+#[must_use]
+#[allow(clippy::missing_const_for_fn)]
+pub fn size() -> usize {
+    #[cfg(target_vendor = "vex")]
+    {
+        let (bottom, top) = bounds();
+        top as usize - bottom as usize
+    }
+
+    #[cfg(not(target_vendor = "vex"))]
+    0
+}
+
+/// Returns the most stack space (in bytes) that the program has used since it started.
+///
+/// This works by searching the stack for the lowest address that no longer holds the
+/// pattern painted at startup, so it takes longer to run the less stack has been used.
+/// Since a function may reserve stack space without writing to all of it, the result is
+/// slightly lower than the true usage in some cases.
+///
+/// # Examples
+///
+/// ```
+/// use vexide::stack;
+///
+/// println!("Used {} of {} bytes of stack", stack::high_water_mark(), stack::size());
+/// ```
+#[must_use]
+#[allow(clippy::missing_const_for_fn)]
+pub fn high_water_mark() -> usize {
+    #[cfg(target_vendor = "vex")]
+    {
+        let (bottom, top) = bounds();
+
+        let mut word = bottom;
+        while word < top && unsafe { word.read_volatile() } == PAINT {
+            word = unsafe { word.add(1) };
+        }
+
+        top as usize - word as usize
+    }
+
+    #[cfg(not(target_vendor = "vex"))]
+    0
+}
+
+/// Returns `true` if the stack has grown into its guard region.
+///
+/// Once this happens, the stack has likely overflowed into the heap, and the program's
+/// memory may be corrupted.
+#[must_use]
+#[allow(clippy::missing_const_for_fn)]
+pub fn is_overflowed() -> bool {
+    #[cfg(target_vendor = "vex")]
+    {
+        let (bottom, _) = bounds();
+
+        // The stack grows downwards, so an overflow reaches the top of the guard region first.
+        (0..GUARD_SIZE / size_of::<u32>())
+            .rev()
+            .any(|offset| unsafe { bottom.add(offset).read_volatile() } != PAINT)
+    }
+
+    #[cfg(not(target_vendor = "vex"))]
+    false
+}
+
+/// Panics if the stack has grown into its guard region.
+///
+/// This is called by the async executor every few milliseconds, but can also be called
+/// manually to check for an overflow sooner (for example, after deeply recursive code).
+///
+/// # Panics
+///
+/// Panics if [`is_overflowed`] returns `true`.
+#[track_caller]
+pub fn check() {
+    assert!(
+        !is_overflowed(),
+        "stack overflow detected (the program used more than {} bytes of stack)",
+        size() - GUARD_SIZE
+    );
+}
@@ -0,0 +1,69 @@
+//! Motor feedforward models.
+
+/// A feedforward model of a DC motor's voltage.
+///
+/// Predicts the voltage needed to move a mechanism at a given velocity and acceleration as
+/// `kS * sign(velocity) + kV * velocity + kA * acceleration`, where `kS` overcomes static
+/// friction, `kV` counters the motor's back-EMF, and `kA` accelerates the mechanism's inertia.
+///
+/// Since feedforward acts on the setpoint rather than on measured error, it does most of the
+/// work of following a setpoint and leaves only small corrections to the feedback controller.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Feedforward {
+    /// The voltage needed to overcome static friction.
+    pub ks: f64,
+
+    /// The voltage needed per RPM of velocity.
+    pub kv: f64,
+
+    /// The voltage needed per RPM per second of acceleration.
+    pub ka: f64,
+}
+
+impl Feedforward {
+    /// Creates a new feedforward model.
+    #[must_use]
+    pub const fn new(ks: f64, kv: f64, ka: f64) -> Self {
+        Self { ks, kv, ka }
+    }
+
+    /// Returns the voltage predicted to move at `velocity` RPM while accelerating at
+    /// `acceleration` RPM per second.
+    #[must_use]
+    pub fn calculate(&self, velocity: f64, acceleration: f64) -> f64 {
+        let static_friction = if velocity == 0.0 {
+            0.0
+        } else {
+            self.ks * velocity.signum()
+        };
+
+        static_friction + self.kv * velocity + self.ka * acceleration
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::control::test::assert_close;
+
+    #[test]
+    fn static_friction_follows_direction() {
+        let feedforward = Feedforward::new(0.5, 0.0, 0.0);
+        assert_close(feedforward.calculate(10.0, 0.0), 0.5);
+        assert_close(feedforward.calculate(-10.0, 0.0), -0.5);
+    }
+
+    #[test]
+    fn no_static_friction_at_rest() {
+        let feedforward = Feedforward::new(0.5, 0.02, 0.001);
+        assert_close(feedforward.calculate(0.0, 0.0), 0.0);
+        assert_close(feedforward.calculate(0.0, 100.0), 0.1);
+    }
+
+    #[test]
+    fn combines_terms() {
+        let feedforward = Feedforward::new(0.5, 0.02, 0.001);
+        assert_close(feedforward.calculate(200.0, 1000.0), 0.5 + 4.0 + 1.0);
+        assert_close(feedforward.calculate(-200.0, -1000.0), -(0.5 + 4.0 + 1.0));
+    }
+}
@@ -0,0 +1,351 @@
+//! Closed-Loop Motor Control
+//!
+//! Smart Motors have built-in position and velocity controllers, but their behavior can't be
+//! inspected and can only be tuned through the `dangerous_motor_tuning` feature. This module
+//! provides controllers that instead run in user code and drive motors with
+//! [`set_voltage`](crate::smart::motor::Motor::set_voltage):
+//!
+//! - [`Pid`] is a PID controller with integral windup clamping and derivative filtering.
+//! - [`Feedforward`] predicts the voltage needed to reach a velocity and acceleration.
+//! - [`ControlLoop`] combines the two to drive a [`Motor`] or [`MotorGroup`] towards a
+//!   [`Setpoint`] at a fixed rate.
+//! - [`TrapezoidalProfile`] and [`SCurveProfile`] plan smooth motions within velocity,
+//!   acceleration, and jerk limits, which [`ProfileFollower`] can then move a motor along.
+//!
+//! # Examples
+//!
+//! Holding a flywheel at a velocity chosen by the driver:
+//!
+//! ```no_run
+//! use core::time::Duration;
+//!
+//! use vexide::{
+//!     devices::control::{ControlLoop, Feedforward, PidGains, Setpoint},
+//!     prelude::*,
+//! };
+//!
+//! #[vexide::main]
+//! async fn main(peripherals: Peripherals) {
+//!     let controller = peripherals.primary_controller;
+//!     let mut flywheel = Motor::new(peripherals.port_1, Gearset::Blue, Direction::Forward);
+//!
+//!     let control = ControlLoop::new(
+//!         PidGains::new(0.02, 0.0, 0.0),
+//!         Feedforward::new(0.3, 0.02, 0.0),
+//!         Setpoint::Velocity(0.0),
+//!     );
+//!
+//!     loop {
+//!         let state = controller.state().unwrap_or_default();
+//!         if state.button_a.is_now_pressed() {
+//!             control.set_setpoint(Setpoint::Velocity(500.0));
+//!         } else if state.button_b.is_now_pressed() {
+//!             control.set_setpoint(Setpoint::Velocity(0.0));
+//!         }
+//!
+//!         _ = control.update(&mut flywheel);
+//!         sleep(Duration::from_millis(10)).await;
+//!     }
+//! }
+//! ```
+//!
+//! Alternatively, [`ControlLoop::run`] runs the loop on its own at a fixed rate, and the
+//! loop's setpoint and gains can be changed from other tasks while it runs.
+
+mod feedforward;
+mod pid;
+mod profile;
+
+use core::{
+    cell::{Cell, RefCell},
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+pub use feedforward::Feedforward;
+pub use pid::{Pid, PidGains};
+pub use profile::{
+    MotionProfile, ProfileFollower, ProfileState, SCurveProfile, TrapezoidalProfile,
+};
+use vexide_core::time::Instant;
+
+use crate::{
+    position::Position,
+    smart::motor::{Motor, MotorError, MotorGroup, MotorGroupError},
+};
+
+/// Something that can be driven by a [`ControlLoop`], such as a [`Motor`] or [`MotorGroup`].
+pub trait ControlTarget {
+    /// The error returned when the target can't be read from or written to.
+    type Error;
+
+    /// Returns the position of the target.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the position couldn't be read.
+    fn position(&self) -> Result<Position, Self::Error>;
+
+    /// Returns the velocity of the target in RPM.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the velocity couldn't be read.
+    fn velocity(&self) -> Result<f64, Self::Error>;
+
+    /// Sets the voltage applied to the target.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the voltage couldn't be set.
+    fn set_voltage(&mut self, volts: f64) -> Result<(), Self::Error>;
+
+    /// Returns the largest voltage that can be applied to the target.
+    fn max_voltage(&self) -> f64;
+}
+
+impl ControlTarget for Motor {
+    type Error = MotorError;
+
+    fn position(&self) -> Result<Position, MotorError> {
+        self.position()
+    }
+
+    fn velocity(&self) -> Result<f64, MotorError> {
+        self.velocity()
+    }
+
+    fn set_voltage(&mut self, volts: f64) -> Result<(), MotorError> {
+        self.set_voltage(volts)
+    }
+
+    fn max_voltage(&self) -> f64 {
+        self.max_voltage()
+    }
+}
+
+impl ControlTarget for MotorGroup {
+    type Error = MotorGroupError;
+
+    fn position(&self) -> Result<Position, MotorGroupError> {
+        self.position()
+    }
+
+    fn velocity(&self) -> Result<f64, MotorGroupError> {
+        self.velocity()
+    }
+
+    fn set_voltage(&mut self, volts: f64) -> Result<(), MotorGroupError> {
+        self.set_voltage(volts)
+    }
+
+    fn max_voltage(&self) -> f64 {
+        self.max_voltage()
+    }
+}
+
+/// A target for a [`ControlLoop`] to reach.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Setpoint {
+    /// Apply a fixed voltage, bypassing the controllers.
+    Voltage(f64),
+
+    /// Hold a velocity in RPM.
+    ///
+    /// The feedforward model provides most of the output, and the PID controller corrects
+    /// for the remaining velocity error (in RPM).
+    Velocity(f64),
+
+    /// Move to and hold a position.
+    ///
+    /// The PID controller acts on the position error in degrees.
+    Position(Position),
+}
+
+/// Drives a [`ControlTarget`] towards a [`Setpoint`] with a [`Pid`] controller and
+/// [`Feedforward`] model.
+///
+/// The setpoint, gains, and feedforward model can all be changed through a shared reference,
+/// including while [`ControlLoop::run`] is running, so that they can be adjusted from another
+/// task.
+///
+/// See the [module-level documentation](self) for an example.
+#[derive(Debug)]
+pub struct ControlLoop {
+    pid: RefCell<Pid>,
+    feedforward: Cell<Feedforward>,
+    setpoint: Cell<Setpoint>,
+    last_update: Cell<Option<Instant>>,
+}
+
+impl ControlLoop {
+    /// Creates a new control loop.
+    #[must_use]
+    pub const fn new(gains: PidGains, feedforward: Feedforward, setpoint: Setpoint) -> Self {
+        Self {
+            pid: RefCell::new(Pid::new(gains)),
+            feedforward: Cell::new(feedforward),
+            setpoint: Cell::new(setpoint),
+            last_update: Cell::new(None),
+        }
+    }
+
+    /// Returns the loop's current setpoint.
+    #[must_use]
+    pub fn setpoint(&self) -> Setpoint {
+        self.setpoint.get()
+    }
+
+    /// Sets the loop's setpoint.
+    ///
+    /// The PID controller is reset if the setpoint changes to a different kind (for example,
+    /// from a velocity to a position), since its accumulated error is no longer meaningful.
+    pub fn set_setpoint(&self, setpoint: Setpoint) {
+        if core::mem::discriminant(&setpoint) != core::mem::discriminant(&self.setpoint.get()) {
+            self.pid.borrow_mut().reset();
+        }
+
+        self.setpoint.set(setpoint);
+    }
+
+    /// Returns the PID controller's gains.
+    #[must_use]
+    pub fn gains(&self) -> PidGains {
+        self.pid.borrow().gains()
+    }
+
+    /// Sets the PID controller's gains.
+    ///
+    /// See [`Pid::set_gains`] for more information.
+    pub fn set_gains(&self, gains: PidGains) {
+        self.pid.borrow_mut().set_gains(gains);
+    }
+
+    /// Returns the feedforward model.
+    #[must_use]
+    pub fn feedforward(&self) -> Feedforward {
+        self.feedforward.get()
+    }
+
+    /// Sets the feedforward model.
+    pub fn set_feedforward(&self, feedforward: Feedforward) {
+        self.feedforward.set(feedforward);
+    }
+
+    /// Returns a mutable reference to the PID controller, for configuring its integral limit
+    /// and derivative filter.
+    pub fn pid_mut(&mut self) -> &mut Pid {
+        self.pid.get_mut()
+    }
+
+    /// Runs one iteration of the loop, setting the target's voltage.
+    ///
+    /// The time since the previous update is measured automatically. This only needs to be
+    /// called directly if the loop is being run as part of another control loop; otherwise,
+    /// use [`ControlLoop::run`].
+    ///
+    /// Returns the voltage that was applied, clamped to the target's maximum voltage.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the target couldn't be read from or written to.
+    pub fn update<T: ControlTarget>(&self, target: &mut T) -> Result<f64, T::Error> {
+        let now = Instant::now();
+        let dt = self
+            .last_update
+            .replace(Some(now))
+            .map_or(Duration::ZERO, |last| now - last);
+
+        let output = match self.setpoint.get() {
+            Setpoint::Voltage(volts) => volts,
+            Setpoint::Velocity(velocity) => {
+                let error = velocity - target.velocity()?;
+                self.feedforward.get().calculate(velocity, 0.0)
+                    + self.pid.borrow_mut().update(error, dt)
+            }
+            Setpoint::Position(position) => {
+                let error = (position - target.position()?).as_degrees();
+                self.pid.borrow_mut().update(error, dt)
+            }
+        };
+
+        let max_voltage = target.max_voltage();
+        let output = output.clamp(-max_voltage, max_voltage);
+        target.set_voltage(output)?;
+
+        Ok(output)
+    }
+
+    /// Runs the loop forever, updating the target once every `interval`.
+    ///
+    /// Only returns if the target can't be read from or written to, in which case the error
+    /// is returned.
+    pub async fn run<T: ControlTarget>(&self, target: &mut T, interval: Duration) -> T::Error {
+        self.last_update.set(None);
+        let mut interval = Interval::new(interval);
+
+        loop {
+            if let Err(error) = self.update(target) {
+                return error;
+            }
+
+            interval.tick().await;
+        }
+    }
+}
+
+/// A fixed-rate schedule for running a loop body once every period.
+pub(crate) struct Interval {
+    period: Duration,
+    next: Instant,
+}
+
+impl Interval {
+    /// Creates a schedule whose first period starts now.
+    pub(crate) fn new(period: Duration) -> Self {
+        Self {
+            period,
+            next: Instant::now(),
+        }
+    }
+
+    /// Returns a future that resolves at the end of the current period.
+    ///
+    /// If the loop body ran late, the missed periods are skipped rather than run back to back.
+    pub(crate) fn tick(&mut self) -> WaitUntil {
+        self.next = (self.next + self.period).max(Instant::now());
+        WaitUntil(self.next)
+    }
+}
+
+/// A future that resolves once a given time has passed.
+pub(crate) struct WaitUntil(pub(crate) Instant);
+
+impl Future for WaitUntil {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if Instant::now() >= self.0 {
+            Poll::Ready(())
+        } else {
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    /// The tolerance used when comparing controller outputs in tests.
+    pub(super) const EPSILON: f64 = 1e-9;
+
+    /// Asserts that `actual` is within [`EPSILON`] of `expected`.
+    pub(super) fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < EPSILON,
+            "expected {expected}, got {actual}"
+        );
+    }
+}
@@ -0,0 +1,244 @@
+//! PID controllers.
+
+use core::time::Duration;
+
+/// Gains for a [`Pid`] controller.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct PidGains {
+    /// The proportional gain, applied to the current error.
+    pub kp: f64,
+
+    /// The integral gain, applied to the error accumulated over time.
+    pub ki: f64,
+
+    /// The derivative gain, applied to the rate of change of the error.
+    pub kd: f64,
+}
+
+impl PidGains {
+    /// Creates a new set of PID gains.
+    #[must_use]
+    pub const fn new(kp: f64, ki: f64, kd: f64) -> Self {
+        Self { kp, ki, kd }
+    }
+}
+
+/// A proportional-integral-derivative controller.
+///
+/// This controller includes two common refinements over a textbook PID controller:
+///
+/// - **Integral windup clamping**: the integral term's contribution to the output is limited
+///   to the [integral limit](Pid::set_integral_limit), so that error accumulated while the
+///   system is saturated or blocked doesn't cause a large overshoot later.
+/// - **Derivative filtering**: the derivative term is passed through a low-pass filter with
+///   a configurable [smoothing factor](Pid::set_derivative_filter), since differentiating noisy
+///   sensor readings greatly amplifies their noise.
+///
+/// # Examples
+///
+/// ```
+/// use core::time::Duration;
+///
+/// use vexide::devices::control::{Pid, PidGains};
+///
+/// let mut pid = Pid::new(PidGains::new(0.5, 0.01, 0.05));
+/// pid.set_integral_limit(2.0);
+///
+/// let output = pid.update(10.0, Duration::from_millis(10));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pid {
+    gains: PidGains,
+    integral_limit: f64,
+    derivative_filter: f64,
+
+    integral: f64,
+    derivative: f64,
+    prev_error: Option<f64>,
+}
+
+impl Pid {
+    /// Creates a new PID controller with the given gains.
+    ///
+    /// The controller starts with no integral limit and no derivative filtering.
+    #[must_use]
+    pub const fn new(gains: PidGains) -> Self {
+        Self {
+            gains,
+            integral_limit: f64::INFINITY,
+            derivative_filter: 0.0,
+            integral: 0.0,
+            derivative: 0.0,
+            prev_error: None,
+        }
+    }
+
+    /// Returns the controller's gains.
+    #[must_use]
+    pub const fn gains(&self) -> PidGains {
+        self.gains
+    }
+
+    /// Sets the controller's gains.
+    ///
+    /// This can be done at any time without resetting the controller. The accumulated error is
+    /// rescaled so that the integral term's output doesn't jump when `ki` changes.
+    pub fn set_gains(&mut self, gains: PidGains) {
+        if gains.ki == 0.0 {
+            self.integral = 0.0;
+        } else {
+            self.integral *= self.gains.ki / gains.ki;
+        }
+
+        self.gains = gains;
+    }
+
+    /// Sets the largest magnitude the integral term may contribute to the output.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `limit` is negative or NaN.
+    pub fn set_integral_limit(&mut self, limit: f64) {
+        assert!(limit >= 0.0, "integral limit must not be negative");
+        self.integral_limit = limit;
+    }
+
+    /// Sets the smoothing factor of the derivative term's low-pass filter.
+    ///
+    /// At 0.0, the derivative is not filtered. Values closer to 1.0 smooth the derivative more,
+    /// at the cost of responding to changes more slowly.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `filter` is not in the range `[0.0, 1.0)`.
+    pub fn set_derivative_filter(&mut self, filter: f64) {
+        assert!(
+            (0.0..1.0).contains(&filter),
+            "derivative filter must be in the range [0.0, 1.0)"
+        );
+        self.derivative_filter = filter;
+    }
+
+    /// Clears the controller's accumulated error and derivative history.
+    pub const fn reset(&mut self) {
+        self.integral = 0.0;
+        self.derivative = 0.0;
+        self.prev_error = None;
+    }
+
+    /// Updates the controller with the current error, returning its output.
+    ///
+    /// `dt` is the time since the previous update. The derivative term is zero on the first
+    /// update after the controller is created or [reset](Pid::reset).
+    pub fn update(&mut self, error: f64, dt: Duration) -> f64 {
+        let dt = dt.as_secs_f64();
+
+        if self.gains.ki != 0.0 {
+            let limit = self.integral_limit / self.gains.ki.abs();
+            self.integral = (self.integral + error * dt).clamp(-limit, limit);
+        }
+
+        if let Some(prev_error) = self.prev_error {
+            if dt > 0.0 {
+                let raw = (error - prev_error) / dt;
+                self.derivative =
+                    self.derivative_filter * self.derivative + (1.0 - self.derivative_filter) * raw;
+            }
+        }
+        self.prev_error = Some(error);
+
+        self.gains.kp * error + self.gains.ki * self.integral + self.gains.kd * self.derivative
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::control::test::{assert_close, EPSILON};
+
+    const DT: Duration = Duration::from_millis(100);
+
+    #[test]
+    fn proportional() {
+        let mut pid = Pid::new(PidGains::new(2.0, 0.0, 0.0));
+        assert_close(pid.update(3.0, DT), 6.0);
+        assert_close(pid.update(-1.5, DT), -3.0);
+    }
+
+    #[test]
+    fn integral_accumulates() {
+        let mut pid = Pid::new(PidGains::new(0.0, 1.0, 0.0));
+        assert_close(pid.update(2.0, DT), 0.2);
+        assert_close(pid.update(2.0, DT), 0.4);
+        assert_close(pid.update(-4.0, DT), 0.0);
+    }
+
+    #[test]
+    fn integral_windup_is_clamped() {
+        let mut pid = Pid::new(PidGains::new(0.0, 2.0, 0.0));
+        pid.set_integral_limit(1.0);
+
+        for _ in 0..100 {
+            assert!(pid.update(10.0, DT) <= 1.0 + EPSILON);
+        }
+        assert_close(pid.update(10.0, DT), 1.0);
+
+        // The clamped integral unwinds immediately once the error changes sign.
+        assert_close(pid.update(-2.5, DT), 0.5);
+
+        for _ in 0..100 {
+            pid.update(-10.0, DT);
+        }
+        assert_close(pid.update(-10.0, DT), -1.0);
+    }
+
+    #[test]
+    fn first_update_has_no_derivative() {
+        let mut pid = Pid::new(PidGains::new(0.0, 0.0, 1.0));
+        assert_close(pid.update(100.0, DT), 0.0);
+        assert_close(pid.update(110.0, DT), 100.0);
+
+        pid.reset();
+        assert_close(pid.update(-50.0, DT), 0.0);
+    }
+
+    #[test]
+    fn derivative_filter_smooths_changes() {
+        let mut pid = Pid::new(PidGains::new(0.0, 0.0, 1.0));
+        pid.set_derivative_filter(0.5);
+
+        pid.update(0.0, DT);
+        // A raw derivative of 10 is halved by the filter, then approaches it over time.
+        assert_close(pid.update(1.0, DT), 5.0);
+        assert_close(pid.update(2.0, DT), 7.5);
+        assert_close(pid.update(3.0, DT), 8.75);
+        // Once the error stops changing, the derivative decays rather than dropping to zero.
+        assert_close(pid.update(3.0, DT), 4.375);
+    }
+
+    #[test]
+    fn zero_dt_is_ignored() {
+        let mut pid = Pid::new(PidGains::new(1.0, 1.0, 1.0));
+        pid.update(1.0, DT);
+        let output = pid.update(2.0, DT);
+
+        // Neither the integral nor the derivative change, and nothing is divided by zero.
+        let repeated = pid.update(2.0, Duration::ZERO);
+        assert!(repeated.is_finite());
+        assert_close(repeated, output);
+    }
+
+    #[test]
+    fn set_gains_keeps_integral_output() {
+        let mut pid = Pid::new(PidGains::new(0.0, 1.0, 0.0));
+        pid.update(5.0, DT);
+        pid.update(5.0, DT);
+
+        pid.set_gains(PidGains::new(0.0, 4.0, 0.0));
+        assert_close(pid.update(0.0, DT), 1.0);
+
+        pid.set_gains(PidGains::new(0.0, 0.0, 0.0));
+        pid.set_gains(PidGains::new(0.0, 4.0, 0.0));
+        assert_close(pid.update(0.0, DT), 0.0);
+    }
+}
@@ -0,0 +1,635 @@
+//! Motion profiles.
+
+use core::time::Duration;
+
+use vexide_core::{float::Float, time::Instant};
+
+use super::{ControlTarget, Feedforward, Interval, Pid, PidGains};
+use crate::position::Position;
+
+/// The state of a [`MotionProfile`] at a point in time.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ProfileState {
+    /// The distance travelled since the start of the profile.
+    pub position: f64,
+
+    /// The rate of change of position, in units per second.
+    pub velocity: f64,
+
+    /// The rate of change of velocity, in units per second squared.
+    pub acceleration: f64,
+}
+
+/// A planned motion over a distance that can be sampled by time.
+///
+/// Profiles are unitless: positions are in whatever unit the profile's distance was given in,
+/// and velocities and accelerations are in that unit per second and per second squared.
+pub trait MotionProfile {
+    /// Returns the time taken to complete the profile.
+    fn duration(&self) -> Duration;
+
+    /// Returns the distance covered by the profile. This is negative for backwards motion.
+    fn distance(&self) -> f64;
+
+    /// Returns the state of the profile `elapsed` time after it started.
+    ///
+    /// Times after the end of the profile return the final state, at rest at the profile's
+    /// distance.
+    fn sample(&self, elapsed: Duration) -> ProfileState;
+}
+
+/// A span of a profile with constant jerk.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct Segment {
+    start: f64,
+    duration: f64,
+    position: f64,
+    velocity: f64,
+    acceleration: f64,
+    jerk: f64,
+}
+
+impl Segment {
+    fn sample(&self, t: f64) -> ProfileState {
+        ProfileState {
+            position: self.position
+                + self.velocity * t
+                + self.acceleration * t * t / 2.0
+                + self.jerk * t * t * t / 6.0,
+            velocity: self.velocity + self.acceleration * t + self.jerk * t * t / 2.0,
+            acceleration: self.acceleration + self.jerk * t,
+        }
+    }
+}
+
+/// A profile made up of up to seven constant-jerk segments.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct PiecewiseProfile {
+    segments: [Segment; 7],
+    len: usize,
+    distance: f64,
+}
+
+impl PiecewiseProfile {
+    const fn new(distance: f64) -> Self {
+        Self {
+            segments: [Segment {
+                start: 0.0,
+                duration: 0.0,
+                position: 0.0,
+                velocity: 0.0,
+                acceleration: 0.0,
+                jerk: 0.0,
+            }; 7],
+            len: 0,
+            distance,
+        }
+    }
+
+    /// Appends a segment, continuing from the end of the previous segment's position and
+    /// velocity. Acceleration and jerk are given for forwards motion.
+    fn push(&mut self, duration: f64, acceleration: f64, jerk: f64) {
+        let sign = self.distance.signum();
+        let (start, end) = match self.len.checked_sub(1) {
+            Some(last) => {
+                let last = self.segments[last];
+                (last.start + last.duration, last.sample(last.duration))
+            }
+            None => (0.0, ProfileState::default()),
+        };
+
+        self.segments[self.len] = Segment {
+            start,
+            duration,
+            position: end.position,
+            velocity: end.velocity,
+            acceleration: acceleration * sign,
+            jerk: jerk * sign,
+        };
+        self.len += 1;
+    }
+
+    fn duration(&self) -> f64 {
+        self.segments[..self.len]
+            .last()
+            .map_or(0.0, |last| last.start + last.duration)
+    }
+
+    fn sample(&self, elapsed: Duration) -> ProfileState {
+        let t = elapsed.as_secs_f64();
+
+        self.segments[..self.len]
+            .iter()
+            .find(|segment| t < segment.start + segment.duration)
+            .map_or(
+                ProfileState {
+                    position: self.distance,
+                    velocity: 0.0,
+                    acceleration: 0.0,
+                },
+                |segment| segment.sample(t - segment.start),
+            )
+    }
+}
+
+fn assert_constraint(value: f64, name: &str) {
+    assert!(
+        value.is_finite() && value > 0.0,
+        "{name} must be finite and positive"
+    );
+}
+
+/// A motion profile with a trapezoidal velocity curve.
+///
+/// The profile accelerates at a constant rate up to its maximum velocity, cruises, then
+/// decelerates at the same rate to a stop. If the distance is too short to reach the maximum
+/// velocity, the cruise is skipped and the velocity curve becomes a triangle.
+///
+/// Acceleration changes instantly at the start and end of each ramp, which can jolt a
+/// mechanism. [`SCurveProfile`] limits jerk to smooth out these changes.
+///
+/// # Examples
+///
+/// ```
+/// use core::time::Duration;
+///
+/// use vexide::devices::control::{MotionProfile, TrapezoidalProfile};
+///
+/// // Move 720 degrees at up to 360 degrees/s, accelerating at 720 degrees/s^2.
+/// let profile = TrapezoidalProfile::new(720.0, 360.0, 720.0);
+///
+/// assert_eq!(profile.duration(), Duration::from_millis(2500));
+/// assert_eq!(profile.sample(Duration::from_secs(1)).velocity, 360.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrapezoidalProfile {
+    segments: PiecewiseProfile,
+    peak_velocity: f64,
+}
+
+impl TrapezoidalProfile {
+    /// Creates a trapezoidal profile covering `distance`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `distance` is not finite.
+    /// - Panics if `max_velocity` or `max_acceleration` is not finite and positive.
+    #[must_use]
+    pub fn new(distance: f64, max_velocity: f64, max_acceleration: f64) -> Self {
+        assert!(distance.is_finite(), "distance must be finite");
+        assert_constraint(max_velocity, "maximum velocity");
+        assert_constraint(max_acceleration, "maximum acceleration");
+
+        let mut segments = PiecewiseProfile::new(distance);
+        let distance = distance.abs();
+        if distance == 0.0 {
+            return Self {
+                segments,
+                peak_velocity: 0.0,
+            };
+        }
+
+        // Distance covered by accelerating to full speed and back down again.
+        let ramp_distance = max_velocity * max_velocity / max_acceleration;
+        let peak_velocity = if ramp_distance <= distance {
+            max_velocity
+        } else {
+            (distance * max_acceleration).sqrt()
+        };
+
+        let ramp_time = peak_velocity / max_acceleration;
+        let cruise_time = (distance - peak_velocity * ramp_time) / peak_velocity;
+
+        segments.push(ramp_time, max_acceleration, 0.0);
+        segments.push(cruise_time.max(0.0), 0.0, 0.0);
+        segments.push(ramp_time, -max_acceleration, 0.0);
+
+        Self {
+            segments,
+            peak_velocity,
+        }
+    }
+
+    /// Returns the highest speed reached by the profile.
+    ///
+    /// This is less than the maximum velocity if the distance was too short to reach it.
+    #[must_use]
+    pub const fn peak_velocity(&self) -> f64 {
+        self.peak_velocity
+    }
+}
+
+impl MotionProfile for TrapezoidalProfile {
+    fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.segments.duration())
+    }
+
+    fn distance(&self) -> f64 {
+        self.segments.distance
+    }
+
+    fn sample(&self, elapsed: Duration) -> ProfileState {
+        self.segments.sample(elapsed)
+    }
+}
+
+/// A jerk-limited motion profile with an S-shaped velocity curve.
+///
+/// Rather than changing acceleration instantly like a [`TrapezoidalProfile`], acceleration
+/// ramps up and down at the maximum jerk, so that the profile can be followed more closely and
+/// with less wear on the mechanism. If the distance is too short to reach the maximum velocity
+/// or acceleration, the profile's peak velocity is lowered until it fits.
+///
+/// # Examples
+///
+/// ```
+/// use vexide::devices::control::{MotionProfile, SCurveProfile};
+///
+/// let profile = SCurveProfile::new(720.0, 360.0, 720.0, 2880.0);
+/// assert_eq!(profile.peak_velocity(), 360.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SCurveProfile {
+    segments: PiecewiseProfile,
+    peak_velocity: f64,
+}
+
+impl SCurveProfile {
+    /// Creates an S-curve profile covering `distance`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `distance` is not finite.
+    /// - Panics if `max_velocity`, `max_acceleration`, or `max_jerk` is not finite and
+    ///   positive.
+    #[must_use]
+    pub fn new(distance: f64, max_velocity: f64, max_acceleration: f64, max_jerk: f64) -> Self {
+        assert!(distance.is_finite(), "distance must be finite");
+        assert_constraint(max_velocity, "maximum velocity");
+        assert_constraint(max_acceleration, "maximum acceleration");
+        assert_constraint(max_jerk, "maximum jerk");
+
+        let mut segments = PiecewiseProfile::new(distance);
+        let distance = distance.abs();
+        if distance == 0.0 {
+            return Self {
+                segments,
+                peak_velocity: 0.0,
+            };
+        }
+
+        // Times spent changing acceleration and at peak acceleration when speeding up to
+        // `velocity`, along with that peak acceleration.
+        let ramp = |velocity: f64| {
+            if velocity * max_jerk >= max_acceleration * max_acceleration {
+                let jerk_time = max_acceleration / max_jerk;
+                (
+                    jerk_time,
+                    velocity / max_acceleration - jerk_time,
+                    max_acceleration,
+                )
+            } else {
+                let jerk_time = (velocity / max_jerk).sqrt();
+                (jerk_time, 0.0, max_jerk * jerk_time)
+            }
+        };
+
+        // The velocity curve while speeding up is symmetric about its midpoint, so the
+        // distance covered is the average velocity times the time taken.
+        let ramp_distance = |velocity: f64| {
+            let (jerk_time, accel_time, _) = ramp(velocity);
+            velocity * (2.0 * jerk_time + accel_time) / 2.0
+        };
+
+        let peak_velocity = if 2.0 * ramp_distance(max_velocity) <= distance {
+            max_velocity
+        } else {
+            // Ramp distance grows monotonically with velocity, so the fastest velocity that
+            // fits can be found by bisection.
+            let (mut low, mut high) = (0.0, max_velocity);
+            for _ in 0..64 {
+                let mid = (low + high) / 2.0;
+                if 2.0 * ramp_distance(mid) <= distance {
+                    low = mid;
+                } else {
+                    high = mid;
+                }
+            }
+            low
+        };
+
+        let (jerk_time, accel_time, peak_acceleration) = ramp(peak_velocity);
+        let cruise_time = (distance - 2.0 * ramp_distance(peak_velocity)) / peak_velocity;
+
+        segments.push(jerk_time, 0.0, max_jerk);
+        segments.push(accel_time, peak_acceleration, 0.0);
+        segments.push(jerk_time, peak_acceleration, -max_jerk);
+        segments.push(cruise_time.max(0.0), 0.0, 0.0);
+        segments.push(jerk_time, 0.0, -max_jerk);
+        segments.push(accel_time, -peak_acceleration, 0.0);
+        segments.push(jerk_time, -peak_acceleration, max_jerk);
+
+        Self {
+            segments,
+            peak_velocity,
+        }
+    }
+
+    /// Returns the highest speed reached by the profile.
+    ///
+    /// This is less than the maximum velocity if the distance was too short to reach it.
+    #[must_use]
+    pub const fn peak_velocity(&self) -> f64 {
+        self.peak_velocity
+    }
+}
+
+impl MotionProfile for SCurveProfile {
+    fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.segments.duration())
+    }
+
+    fn distance(&self) -> f64 {
+        self.segments.distance
+    }
+
+    fn sample(&self, elapsed: Duration) -> ProfileState {
+        self.segments.sample(elapsed)
+    }
+}
+
+/// Moves a [`ControlTarget`] along a [`MotionProfile`].
+///
+/// The profile is interpreted in degrees, so its velocities are in degrees per second and its
+/// accelerations are in degrees per second squared. At each update, the follower applies the
+/// voltage predicted by its [`Feedforward`] model for the profile's velocity and acceleration,
+/// and corrects for drift from the profile's position with a [`Pid`] controller acting on the
+/// position error in degrees.
+///
+/// # Examples
+///
+/// ```no_run
+/// use core::time::Duration;
+///
+/// use vexide::{
+///     devices::control::{Feedforward, PidGains, ProfileFollower, SCurveProfile},
+///     prelude::*,
+/// };
+///
+/// #[vexide::main]
+/// async fn main(peripherals: Peripherals) {
+///     let mut arm = Motor::new(peripherals.port_1, Gearset::Red, Direction::Forward);
+///     let mut follower = ProfileFollower::new(
+///         PidGains::new(0.05, 0.0, 0.002),
+///         Feedforward::new(0.4, 0.1, 0.01),
+///     );
+///
+///     // Raise the arm 90 degrees.
+///     let profile = SCurveProfile::new(90.0, 180.0, 360.0, 1440.0);
+///     _ = follower
+///         .follow(&mut arm, &profile, Duration::from_millis(10))
+///         .await;
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileFollower {
+    pid: Pid,
+    feedforward: Feedforward,
+}
+
+impl ProfileFollower {
+    /// Creates a new profile follower.
+    #[must_use]
+    pub const fn new(gains: PidGains, feedforward: Feedforward) -> Self {
+        Self {
+            pid: Pid::new(gains),
+            feedforward,
+        }
+    }
+
+    /// Returns a mutable reference to the follower's PID controller.
+    pub const fn pid_mut(&mut self) -> &mut Pid {
+        &mut self.pid
+    }
+
+    /// Returns the follower's feedforward model.
+    #[must_use]
+    pub const fn feedforward(&self) -> Feedforward {
+        self.feedforward
+    }
+
+    /// Sets the follower's feedforward model.
+    pub const fn set_feedforward(&mut self, feedforward: Feedforward) {
+        self.feedforward = feedforward;
+    }
+
+    /// Moves `target` along `profile`, starting from its current position and updating once
+    /// every `interval`.
+    ///
+    /// Returns once the profile has finished, leaving the target with no voltage applied. To
+    /// hold the final position, drive the target with a
+    /// [`ControlLoop`](super::ControlLoop) afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the target couldn't be read from or written to.
+    pub async fn follow<T: ControlTarget, P: MotionProfile>(
+        &mut self,
+        target: &mut T,
+        profile: &P,
+        interval: Duration,
+    ) -> Result<(), T::Error> {
+        /// Converts from degrees per second to RPM.
+        const RPM_PER_DEGREE_PER_SECOND: f64 = 60.0 / 360.0;
+
+        self.pid.reset();
+
+        let origin = target.position()?;
+        let start = Instant::now();
+        let duration = profile.duration();
+        let mut last_update = start;
+        let mut interval = Interval::new(interval);
+
+        loop {
+            let now = Instant::now();
+            let elapsed = now - start;
+            if elapsed >= duration {
+                break;
+            }
+
+            let state = profile.sample(elapsed);
+            let setpoint = origin + Position::from_degrees(state.position);
+            let error = (setpoint - target.position()?).as_degrees();
+
+            let output = self.feedforward.calculate(
+                state.velocity * RPM_PER_DEGREE_PER_SECOND,
+                state.acceleration * RPM_PER_DEGREE_PER_SECOND,
+            ) + self.pid.update(error, now - last_update);
+            last_update = now;
+
+            let max_voltage = target.max_voltage();
+            target.set_voltage(output.clamp(-max_voltage, max_voltage))?;
+
+            interval.tick().await;
+        }
+
+        target.set_voltage(0.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::control::test::{assert_close, EPSILON};
+
+    /// Samples a profile every millisecond, including its end.
+    fn samples(profile: &impl MotionProfile) -> impl Iterator<Item = ProfileState> + '_ {
+        let millis = profile.duration().as_millis() as u64 + 1;
+        (0..=millis).map(|ms| profile.sample(Duration::from_millis(ms)))
+    }
+
+    #[test]
+    fn trapezoidal_cruises() {
+        let profile = TrapezoidalProfile::new(100.0, 50.0, 100.0);
+
+        assert_close(profile.duration().as_secs_f64(), 2.5);
+        assert_close(profile.peak_velocity(), 50.0);
+
+        let ramp = profile.sample(Duration::from_millis(250));
+        assert_close(ramp.position, 3.125);
+        assert_close(ramp.velocity, 25.0);
+        assert_close(ramp.acceleration, 100.0);
+
+        let cruise = profile.sample(Duration::from_secs(1));
+        assert_close(cruise.position, 37.5);
+        assert_close(cruise.velocity, 50.0);
+        assert_close(cruise.acceleration, 0.0);
+
+        let braking = profile.sample(Duration::from_millis(2250));
+        assert_close(braking.position, 100.0 - 3.125);
+        assert_close(braking.velocity, 25.0);
+        assert_close(braking.acceleration, -100.0);
+    }
+
+    #[test]
+    fn trapezoidal_short_distance_is_triangular() {
+        let profile = TrapezoidalProfile::new(1.0, 50.0, 100.0);
+
+        assert_close(profile.peak_velocity(), 10.0);
+        assert_close(profile.duration().as_secs_f64(), 0.2);
+
+        let peak = profile.sample(Duration::from_millis(100));
+        assert_close(peak.position, 0.5);
+        assert_close(peak.velocity, 10.0);
+    }
+
+    #[test]
+    fn backwards_profiles_are_mirrored() {
+        let forwards = TrapezoidalProfile::new(100.0, 50.0, 100.0);
+        let backwards = TrapezoidalProfile::new(-100.0, 50.0, 100.0);
+        assert_eq!(forwards.duration(), backwards.duration());
+        for (f, b) in samples(&forwards).zip(samples(&backwards)) {
+            assert_close(b.position, -f.position);
+            assert_close(b.velocity, -f.velocity);
+            assert_close(b.acceleration, -f.acceleration);
+        }
+
+        let forwards = SCurveProfile::new(100.0, 50.0, 100.0, 400.0);
+        let backwards = SCurveProfile::new(-100.0, 50.0, 100.0, 400.0);
+        assert_eq!(forwards.duration(), backwards.duration());
+        for (f, b) in samples(&forwards).zip(samples(&backwards)) {
+            assert_close(b.position, -f.position);
+            assert_close(b.velocity, -f.velocity);
+            assert_close(b.acceleration, -f.acceleration);
+        }
+    }
+
+    #[test]
+    fn zero_distance() {
+        let trapezoidal = TrapezoidalProfile::new(0.0, 50.0, 100.0);
+        let s_curve = SCurveProfile::new(0.0, 50.0, 100.0, 400.0);
+
+        assert_eq!(trapezoidal.duration(), Duration::ZERO);
+        assert_eq!(s_curve.duration(), Duration::ZERO);
+        assert_eq!(trapezoidal.sample(Duration::ZERO), ProfileState::default());
+        assert_eq!(s_curve.sample(Duration::ZERO), ProfileState::default());
+    }
+
+    #[test]
+    fn s_curve_reaches_limits() {
+        let profile = SCurveProfile::new(100.0, 50.0, 100.0, 400.0);
+
+        // 0.25s of jerk up to full acceleration, 0.25s at full acceleration, and 0.25s of
+        // jerk back down, covering 18.75 units. The remaining 62.5 units are covered while
+        // cruising for 1.25s.
+        assert_close(profile.peak_velocity(), 50.0);
+        assert_close(profile.duration().as_secs_f64(), 2.75);
+
+        let accelerating = profile.sample(Duration::from_millis(375));
+        assert_close(accelerating.acceleration, 100.0);
+
+        let cruise = profile.sample(Duration::from_millis(1375));
+        assert_close(cruise.position, 50.0);
+        assert_close(cruise.velocity, 50.0);
+        assert_close(cruise.acceleration, 0.0);
+    }
+
+    #[test]
+    fn s_curve_short_distance_lowers_peak_velocity() {
+        let profile = SCurveProfile::new(2.0, 50.0, 100.0, 400.0);
+
+        assert!(profile.peak_velocity() < 50.0);
+
+        // The midpoint is rounded to the nearest nanosecond, so it's only approximately exact.
+        let midpoint = profile.sample(profile.duration() / 2);
+        assert!((midpoint.position - 1.0).abs() < 1e-6);
+        assert!((midpoint.velocity - profile.peak_velocity()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn s_curve_respects_constraints() {
+        for distance in [0.5, 5.0, 20.0, 100.0, 1000.0] {
+            let profile = SCurveProfile::new(distance, 50.0, 100.0, 400.0);
+
+            let mut previous: Option<ProfileState> = None;
+            for state in samples(&profile) {
+                assert!(state.velocity >= -EPSILON && state.velocity <= 50.0 + EPSILON);
+                assert!(state.acceleration.abs() <= 100.0 + EPSILON);
+
+                if let Some(previous) = previous {
+                    // Velocity and acceleration are continuous, so they can only change by a
+                    // small amount over a millisecond.
+                    assert!(state.position >= previous.position - EPSILON);
+                    assert!((state.velocity - previous.velocity).abs() <= 0.1 + EPSILON);
+                    assert!((state.acceleration - previous.acceleration).abs() <= 0.4 + EPSILON);
+                }
+                previous = Some(state);
+            }
+
+            let end = profile.sample(profile.duration());
+            assert_close(end.position, distance);
+            assert_close(end.velocity, 0.0);
+        }
+    }
+
+    #[test]
+    fn profiles_end_at_distance() {
+        for distance in [-250.0, -3.0, 0.25, 42.0, 10_000.0] {
+            let trapezoidal = TrapezoidalProfile::new(distance, 50.0, 100.0);
+            let s_curve = SCurveProfile::new(distance, 50.0, 100.0, 400.0);
+
+            for profile in [&trapezoidal as &dyn MotionProfile, &s_curve] {
+                assert_close(profile.distance(), distance);
+
+                // Integrating the final segment should land on the distance too, not only
+                // the clamped state after the profile ends.
+                let almost = profile.sample(profile.duration() - Duration::from_nanos(1));
+                assert!((almost.position - distance).abs() < 1e-3);
+
+                let end = profile.sample(profile.duration() + Duration::from_secs(1));
+                assert_close(end.position, distance);
+                assert_close(end.velocity, 0.0);
+            }
+        }
+    }
+}
@@ -966,6 +966,8 @@ impl Controller {
     ///
     /// - A [`ControllerError::Offline`] error is returned if the controller is
     ///   not connected.
+    /// - A [`ControllerError::WriteBusy`] error is returned if the controller's screen was
+    ///   written to too recently.
     ///
     /// # Panics
     ///
@@ -983,7 +985,19 @@ impl Controller {
     /// }
     /// ```
     pub fn try_rumble(&mut self, pattern: impl AsRef<str>) -> Result<(), ControllerError> {
-        self.screen.try_set_text(pattern, 4, 1)
+        validate_connection(self.screen.id)?;
+
+        let id: V5_ControllerId = self.screen.id.into();
+        let pattern = CString::new(pattern.as_ref())
+            .expect("A NUL (0x00) character was found in the text input string.");
+
+        // Rumble patterns are written to the hidden fourth line of the screen, so this can't
+        // go through `try_set_text`, which only accepts visible lines.
+        if unsafe { vexControllerTextSet(u32::from(id.0), 4, 0, pattern.as_ptr().cast()) } != 1 {
+            return Err(ControllerError::WriteBusy);
+        }
+
+        Ok(())
     }
 }
 
@@ -0,0 +1,427 @@
+//! Differential Drivetrains
+//!
+//! A differential (or "tank") drivetrain steers by driving the wheels on each side of the robot
+//! at different speeds. [`DifferentialDrive`] mixes joystick inputs into speeds for each side,
+//! in one of three common control schemes:
+//!
+//! - [Tank drive](DifferentialDrive::tank), where each joystick controls one side.
+//! - [Arcade drive](DifferentialDrive::arcade), where one axis drives forwards and another
+//!   turns.
+//! - [Curvature drive](DifferentialDrive::curvature), where one axis drives forwards and
+//!   another sets how sharply the robot turns, so that turning sensitivity doesn't change with
+//!   speed.
+//!
+//! Given the geometry of the drivetrain, it can also be commanded with
+//! [chassis speeds](DifferentialDrive::set_chassis_speeds) in real-world units, which is the
+//! usual interface for autonomous motion controllers.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use vexide::{
+//!     devices::{drive::DifferentialDrive, position::Length},
+//!     prelude::*,
+//! };
+//!
+//! #[vexide::main]
+//! async fn main(peripherals: Peripherals) {
+//!     let controller = peripherals.primary_controller;
+//!     let mut drive = DifferentialDrive::new(
+//!         MotorGroup::new([
+//!             Motor::new(peripherals.port_1, Gearset::Blue, Direction::Reverse),
+//!             Motor::new(peripherals.port_2, Gearset::Blue, Direction::Reverse),
+//!         ]),
+//!         MotorGroup::new([
+//!             Motor::new(peripherals.port_3, Gearset::Blue, Direction::Forward),
+//!             Motor::new(peripherals.port_4, Gearset::Blue, Direction::Forward),
+//!         ]),
+//!         Length::from_inches(12.0),
+//!         Length::from_inches(3.25),
+//!     );
+//!
+//!     loop {
+//!         let state = controller.state().unwrap_or_default();
+//!         _ = drive.arcade(state.left_stick.y(), state.right_stick.x());
+//!
+//!         sleep(Controller::UPDATE_INTERVAL).await;
+//!     }
+//! }
+//! ```
+
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+
+use vexide_core::float::Float;
+
+use crate::{
+    position::{AngularVelocity, Length, Velocity},
+    smart::motor::{BrakeMode, MotorGroup, MotorGroupError},
+};
+
+/// How a [`DifferentialDrive`] commands its motors.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum DriveMode {
+    /// Inputs are scaled to a fraction of the motors' maximum voltage.
+    ///
+    /// This gives the most direct feel, but the robot's speed will vary with its load and
+    /// battery level.
+    #[default]
+    Voltage,
+
+    /// Inputs are scaled to a fraction of the motors' maximum velocity, which the motors'
+    /// built-in velocity controllers then try to hold.
+    Velocity,
+}
+
+/// The velocity of a drivetrain's chassis.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ChassisSpeeds {
+    /// The forwards velocity of the chassis.
+    pub linear: Velocity,
+
+    /// The counterclockwise angular velocity of the chassis.
+    pub angular: AngularVelocity,
+}
+
+/// A drivetrain with independently-driven left and right sides.
+///
+/// Joystick-style inputs (to [`tank`](Self::tank), [`arcade`](Self::arcade), and
+/// [`curvature`](Self::curvature)) are fractions of full speed from -1.0 to 1.0. When mixing
+/// two inputs would ask for more than full speed on one side, both sides are scaled down
+/// together ("desaturated") so that the robot still turns along the intended arc instead of
+/// clipping to a different one.
+///
+/// See the [module-level documentation](self) for an example.
+#[derive(Debug, PartialEq)]
+pub struct DifferentialDrive {
+    left: MotorGroup,
+    right: MotorGroup,
+    track_width: Length,
+    wheel_diameter: Length,
+    mode: DriveMode,
+}
+
+impl DifferentialDrive {
+    /// Creates a new differential drivetrain.
+    ///
+    /// `track_width` is the distance between the centers of the left and right wheels, and
+    /// `wheel_diameter` is the diameter of the driven wheels. If the wheels are geared, set the
+    /// [gear ratio](crate::smart::motor::Motor::set_gear_ratio) of each motor so that their
+    /// velocities are measured at the wheels.
+    ///
+    /// The drivetrain starts in [`DriveMode::Voltage`].
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `track_width` or `wheel_diameter` is not finite and positive.
+    #[must_use]
+    pub fn new(
+        left: MotorGroup,
+        right: MotorGroup,
+        track_width: Length,
+        wheel_diameter: Length,
+    ) -> Self {
+        assert!(
+            track_width.as_meters().is_finite() && track_width.as_meters() > 0.0,
+            "track width must be finite and positive"
+        );
+        assert!(
+            wheel_diameter.as_meters().is_finite() && wheel_diameter.as_meters() > 0.0,
+            "wheel diameter must be finite and positive"
+        );
+
+        Self {
+            left,
+            right,
+            track_width,
+            wheel_diameter,
+            mode: DriveMode::default(),
+        }
+    }
+
+    /// Returns the motors on the left side of the drivetrain.
+    #[must_use]
+    pub const fn left(&self) -> &MotorGroup {
+        &self.left
+    }
+
+    /// Returns a mutable reference to the motors on the left side of the drivetrain.
+    pub const fn left_mut(&mut self) -> &mut MotorGroup {
+        &mut self.left
+    }
+
+    /// Returns the motors on the right side of the drivetrain.
+    #[must_use]
+    pub const fn right(&self) -> &MotorGroup {
+        &self.right
+    }
+
+    /// Returns a mutable reference to the motors on the right side of the drivetrain.
+    pub const fn right_mut(&mut self) -> &mut MotorGroup {
+        &mut self.right
+    }
+
+    /// Consumes the drivetrain, returning its left and right motor groups.
+    #[must_use]
+    pub fn into_sides(self) -> (MotorGroup, MotorGroup) {
+        (self.left, self.right)
+    }
+
+    /// Returns the distance between the centers of the left and right wheels.
+    #[must_use]
+    pub const fn track_width(&self) -> Length {
+        self.track_width
+    }
+
+    /// Returns the diameter of the driven wheels.
+    #[must_use]
+    pub const fn wheel_diameter(&self) -> Length {
+        self.wheel_diameter
+    }
+
+    /// Returns how the drivetrain commands its motors.
+    #[must_use]
+    pub const fn mode(&self) -> DriveMode {
+        self.mode
+    }
+
+    /// Sets how the drivetrain commands its motors.
+    pub const fn set_mode(&mut self, mode: DriveMode) {
+        self.mode = mode;
+    }
+
+    /// Drives each side of the drivetrain at a fraction of full speed.
+    ///
+    /// Inputs outside of `[-1.0, 1.0]` are clamped.
+    ///
+    /// # Errors
+    ///
+    /// - A [`MotorGroupError`] is returned if any of the motors failed to receive the command,
+    ///   containing the errors from both sides of the drivetrain.
+    pub fn tank(&mut self, left: f64, right: f64) -> Result<(), MotorGroupError> {
+        self.drive(left.clamp(-1.0, 1.0), right.clamp(-1.0, 1.0))
+    }
+
+    /// Drives forwards at a fraction of full speed while turning at a fraction of full speed.
+    ///
+    /// Positive values of `turn` turn clockwise (to the right), matching the x-axis of a
+    /// controller's joysticks. Inputs outside of `[-1.0, 1.0]` are clamped, and the mixed
+    /// output is desaturated.
+    ///
+    /// # Errors
+    ///
+    /// - A [`MotorGroupError`] is returned if any of the motors failed to receive the command,
+    ///   containing the errors from both sides of the drivetrain.
+    pub fn arcade(&mut self, forward: f64, turn: f64) -> Result<(), MotorGroupError> {
+        let (left, right) = arcade_mix(forward, turn);
+        self.drive(left, right)
+    }
+
+    /// Drives forwards at a fraction of full speed along an arc of the given curvature.
+    ///
+    /// Unlike [`arcade`](Self::arcade), the turning rate is proportional to the forward speed,
+    /// so the robot follows the same arc regardless of how fast it is driving. Since this makes
+    /// turning in place impossible, `quick_turn` can be set to instead treat `curvature` as
+    /// an arcade-style turning speed. Teams commonly map `quick_turn` to a button, or enable it
+    /// whenever the forward input is near zero.
+    ///
+    /// Positive values of `curvature` turn clockwise (to the right). Inputs outside of
+    /// `[-1.0, 1.0]` are clamped, and the mixed output is desaturated.
+    ///
+    /// # Errors
+    ///
+    /// - A [`MotorGroupError`] is returned if any of the motors failed to receive the command,
+    ///   containing the errors from both sides of the drivetrain.
+    pub fn curvature(
+        &mut self,
+        forward: f64,
+        curvature: f64,
+        quick_turn: bool,
+    ) -> Result<(), MotorGroupError> {
+        let (left, right) = curvature_mix(forward, curvature, quick_turn);
+        self.drive(left, right)
+    }
+
+    /// Drives the chassis at a linear and angular velocity.
+    ///
+    /// If a side of the drivetrain can't reach the speed needed for the command, both sides are
+    /// slowed down proportionally so that the chassis still follows the commanded arc.
+    ///
+    /// In [`DriveMode::Voltage`], speeds are converted to voltages assuming voltage is
+    /// proportional to speed, so the chassis will only approximately reach the commanded
+    /// speeds. Use [`DriveMode::Velocity`] for closed-loop control.
+    ///
+    /// # Errors
+    ///
+    /// - A [`MotorGroupError`] is returned if the motors' maximum velocities couldn't be read,
+    ///   or if any of the motors failed to receive the command. It contains the errors from
+    ///   both sides of the drivetrain.
+    pub fn set_chassis_speeds(&mut self, speeds: ChassisSpeeds) -> Result<(), MotorGroupError> {
+        let (max_left, max_right) = join(self.left.max_velocity(), self.right.max_velocity())?;
+
+        let half_track = self.track_width.as_meters() / 2.0;
+        let angular = speeds.angular.as_radians_per_second();
+        let linear = speeds.linear.as_meters_per_second();
+
+        let left = self.wheel_rpm(linear - angular * half_track) / max_left;
+        let right = self.wheel_rpm(linear + angular * half_track) / max_right;
+
+        let (left, right) = desaturate(left, right);
+        self.drive(left, right)
+    }
+
+    /// Returns the measured velocity of the chassis.
+    ///
+    /// # Errors
+    ///
+    /// - A [`MotorGroupError`] is returned if either side's velocity couldn't be read.
+    pub fn chassis_speeds(&self) -> Result<ChassisSpeeds, MotorGroupError> {
+        let (left, right) = join(self.left.velocity(), self.right.velocity())?;
+        let left = self.wheel_speed(left);
+        let right = self.wheel_speed(right);
+
+        Ok(ChassisSpeeds {
+            linear: Velocity::from_meters_per_second((left + right) / 2.0),
+            angular: AngularVelocity::from_radians_per_second(
+                (right - left) / self.track_width.as_meters(),
+            ),
+        })
+    }
+
+    /// Stops the drivetrain using the given brake mode.
+    ///
+    /// # Errors
+    ///
+    /// - A [`MotorGroupError`] is returned if any of the motors failed to receive the command,
+    ///   containing the errors from both sides of the drivetrain.
+    pub fn brake(&mut self, mode: BrakeMode) -> Result<(), MotorGroupError> {
+        join(self.left.brake(mode), self.right.brake(mode)).map(|_| ())
+    }
+
+    /// Converts a wheel's surface speed in m/s to its angular velocity in RPM.
+    fn wheel_rpm(&self, meters_per_second: f64) -> f64 {
+        meters_per_second / (PI * self.wheel_diameter.as_meters()) * 60.0
+    }
+
+    /// Converts a wheel's angular velocity in RPM to its surface speed in m/s.
+    fn wheel_speed(&self, rpm: f64) -> f64 {
+        rpm / 60.0 * PI * self.wheel_diameter.as_meters()
+    }
+
+    /// Drives each side at a fraction of full speed, according to the drive mode.
+    fn drive(&mut self, left: f64, right: f64) -> Result<(), MotorGroupError> {
+        let mode = self.mode;
+        let drive_side = |side: &mut MotorGroup, fraction: f64| match mode {
+            DriveMode::Voltage => side.set_voltage(fraction * side.max_voltage()),
+            DriveMode::Velocity => {
+                let rpm = fraction * side.max_velocity()?;
+                side.set_velocity(rpm.round() as i32)
+            }
+        };
+
+        join(
+            drive_side(&mut self.left, left),
+            drive_side(&mut self.right, right),
+        )
+        .map(|_| ())
+    }
+}
+
+/// Mixes arcade-style inputs into left and right speeds.
+fn arcade_mix(forward: f64, turn: f64) -> (f64, f64) {
+    let forward = forward.clamp(-1.0, 1.0);
+    let turn = turn.clamp(-1.0, 1.0);
+
+    desaturate(forward + turn, forward - turn)
+}
+
+/// Mixes curvature-style inputs into left and right speeds.
+fn curvature_mix(forward: f64, curvature: f64, quick_turn: bool) -> (f64, f64) {
+    let forward = forward.clamp(-1.0, 1.0);
+    let curvature = curvature.clamp(-1.0, 1.0);
+
+    let turn = if quick_turn {
+        curvature
+    } else {
+        forward.abs() * curvature
+    };
+
+    desaturate(forward + turn, forward - turn)
+}
+
+/// Scales a pair of speeds down so that neither exceeds full speed, preserving their ratio.
+fn desaturate(left: f64, right: f64) -> (f64, f64) {
+    let scale = left.abs().max(right.abs()).max(1.0);
+    (left / scale, right / scale)
+}
+
+/// Combines the results of commanding both sides of a drivetrain, merging their errors.
+fn join<L, R>(
+    left: Result<L, MotorGroupError>,
+    right: Result<R, MotorGroupError>,
+) -> Result<(L, R), MotorGroupError> {
+    match (left, right) {
+        (Ok(left), Ok(right)) => Ok((left, right)),
+        (left, right) => {
+            let errors: Vec<_> = left
+                .err()
+                .into_iter()
+                .chain(right.err())
+                .flat_map(|error| error.errors)
+                .collect();
+            Err(MotorGroupError { errors })
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EPSILON: f64 = 1e-9;
+
+    fn assert_close((left, right): (f64, f64), (expected_left, expected_right): (f64, f64)) {
+        assert!(
+            (left - expected_left).abs() < EPSILON && (right - expected_right).abs() < EPSILON,
+            "expected ({expected_left}, {expected_right}), got ({left}, {right})"
+        );
+    }
+
+    #[test]
+    fn desaturate_keeps_unsaturated_speeds() {
+        assert_close(desaturate(0.5, -0.25), (0.5, -0.25));
+        assert_close(desaturate(1.0, -1.0), (1.0, -1.0));
+    }
+
+    #[test]
+    fn desaturate_preserves_ratio() {
+        assert_close(desaturate(2.0, 1.0), (1.0, 0.5));
+        assert_close(desaturate(-0.5, -1.5), (-1.0 / 3.0, -1.0));
+    }
+
+    #[test]
+    fn arcade_mixing() {
+        assert_close(arcade_mix(0.5, 0.0), (0.5, 0.5));
+        // Positive turns are clockwise, so the left side drives faster.
+        assert_close(arcade_mix(0.0, 0.5), (0.5, -0.5));
+        assert_close(arcade_mix(1.0, 1.0), (1.0, 0.0));
+        assert_close(arcade_mix(0.5, -0.25), (0.25, 0.75));
+        // Inputs are clamped before mixing.
+        assert_close(arcade_mix(3.0, 0.0), (1.0, 1.0));
+    }
+
+    #[test]
+    fn curvature_scales_turn_with_speed() {
+        assert_close(curvature_mix(0.5, 0.5, false), (0.75, 0.25));
+        assert_close(curvature_mix(-0.5, 0.5, false), (-0.25, -0.75));
+        assert_close(curvature_mix(1.0, 1.0, false), (1.0, 0.0));
+        // Without quick turn, the robot can't turn in place.
+        assert_close(curvature_mix(0.0, 1.0, false), (0.0, 0.0));
+    }
+
+    #[test]
+    fn curvature_quick_turn() {
+        assert_close(curvature_mix(0.0, 1.0, true), (1.0, -1.0));
+        assert_close(curvature_mix(0.5, 0.5, true), (1.0, 0.0));
+    }
+}
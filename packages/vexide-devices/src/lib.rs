@@ -44,9 +44,12 @@ extern crate alloc;
 
 pub mod adi;
 pub mod battery;
+pub mod control;
 pub mod controller;
 pub mod display;
+pub mod drive;
 pub mod math;
+pub mod odometry;
 pub mod peripherals;
 pub mod position;
 pub mod rgb;
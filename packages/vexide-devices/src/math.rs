@@ -1,6 +0,0 @@
-//! Math-related Container Types
-//!
-//! This module re-exports several math-related types from the [`mint`] crate
-//! for usage in vexide's device APIs.
-
-pub use mint::{EulerAngles, Point2, Quaternion, Vector3};
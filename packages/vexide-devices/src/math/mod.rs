@@ -0,0 +1,121 @@
+//! Math-related Container Types
+//!
+//! This module re-exports several math-related types from the [`mint`] crate
+//! for usage in vexide's device APIs.
+//!
+//! It also provides types for 2D robot geometry:
+//!
+//! - [`Vec2`] is a two-dimensional vector.
+//! - [`Pose2d`] is a position and heading on the field.
+//! - [`Transform2d`] is a motion relative to a pose.
+//!
+//! These convert to and from the [`mint`] types, and follow the usual mathematical conventions
+//! for angles: radians, measured counterclockwise from the positive x-axis. Sensor readings
+//! such as [`InertialSensor::heading`] are measured _clockwise_ in degrees, and should be
+//! converted with [`from_clockwise_degrees`] before being used with these types.
+//!
+//! [`InertialSensor::heading`]: crate::smart::imu::InertialSensor::heading
+
+mod pose;
+mod vec2;
+
+use core::f64::consts::{PI, TAU};
+
+pub use mint::{EulerAngles, Point2, Quaternion, Vector3};
+pub use pose::{Pose2d, Transform2d};
+pub use vec2::Vec2;
+use vexide_core::float::Float;
+
+/// Wraps an angle in radians to the range `[-π, π)`.
+///
+/// # Examples
+///
+/// ```
+/// use core::f64::consts::PI;
+///
+/// use vexide::devices::math::wrap_angle;
+///
+/// assert!((wrap_angle(3.0 * PI / 2.0) + PI / 2.0).abs() < 1e-9);
+/// ```
+#[must_use]
+pub fn wrap_angle(radians: f64) -> f64 {
+    (radians + PI).rem_euclid(TAU) - PI
+}
+
+/// Wraps an angle in degrees to the range `[-180, 180)`.
+#[must_use]
+pub fn wrap_degrees(degrees: f64) -> f64 {
+    (degrees + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// Returns the signed angle in radians of the shortest rotation from `from` to `to`.
+///
+/// The result is positive if the rotation is counterclockwise.
+#[must_use]
+pub fn angle_difference(from: f64, to: f64) -> f64 {
+    wrap_angle(to - from)
+}
+
+/// Converts a clockwise angle in degrees, as reported by VEX sensors, into a counterclockwise
+/// angle in radians in the range `[-π, π)`.
+#[must_use]
+pub fn from_clockwise_degrees(degrees: f64) -> f64 {
+    wrap_angle(-degrees.to_radians())
+}
+
+/// Converts a counterclockwise angle in radians into a clockwise angle in degrees in the range
+/// `[0, 360)`, matching the range of [`InertialSensor::heading`].
+///
+/// [`InertialSensor::heading`]: crate::smart::imu::InertialSensor::heading
+#[must_use]
+pub fn to_clockwise_degrees(radians: f64) -> f64 {
+    (-radians.to_degrees()).rem_euclid(360.0)
+}
+
+#[cfg(test)]
+mod test {
+    use core::f64::consts::FRAC_PI_2;
+
+    use super::*;
+
+    const EPSILON: f64 = 1e-9;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < EPSILON,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn wrap_angle_boundaries() {
+        assert_close(wrap_angle(PI), -PI);
+        assert_close(wrap_angle(-PI), -PI);
+        assert_close(wrap_angle(3.0 * PI), -PI);
+        assert_close(wrap_angle(PI - 1e-6), PI - 1e-6);
+        assert_close(wrap_angle(-PI + 1e-6), -PI + 1e-6);
+        assert_close(wrap_angle(TAU + 1.0), 1.0);
+        assert_close(wrap_degrees(180.0), -180.0);
+        assert_close(wrap_degrees(-180.0), -180.0);
+    }
+
+    #[test]
+    fn clockwise_degrees_conversions() {
+        assert_close(from_clockwise_degrees(0.0), 0.0);
+        assert_close(from_clockwise_degrees(90.0), -FRAC_PI_2);
+        assert_close(from_clockwise_degrees(270.0), FRAC_PI_2);
+        assert_close(from_clockwise_degrees(180.0), -PI);
+
+        assert_close(to_clockwise_degrees(0.0), 0.0);
+        assert_close(to_clockwise_degrees(FRAC_PI_2), 270.0);
+        assert_close(to_clockwise_degrees(-FRAC_PI_2), 90.0);
+        assert_close(to_clockwise_degrees(PI), 180.0);
+
+        for heading in [0.0, 45.0, 180.0, 300.0, 359.5] {
+            assert_close(
+                to_clockwise_degrees(from_clockwise_degrees(heading)),
+                heading,
+            );
+        }
+    }
+}
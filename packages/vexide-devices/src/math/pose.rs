@@ -0,0 +1,309 @@
+//! Robot poses and rigid transforms.
+
+use core::ops::{Add, Sub};
+
+use mint::{EulerAngles, Point2};
+
+use super::{wrap_angle, Vec2};
+
+/// The position and heading of a robot on a plane.
+///
+/// The heading is in radians, measured counterclockwise from the positive x-axis. Note that
+/// VEX sensors report yaw and heading _clockwise_; see [`Pose2d::from_sensors`] and
+/// [`from_clockwise_degrees`](super::from_clockwise_degrees) for converting their readings.
+///
+/// # Examples
+///
+/// ```
+/// use core::f64::consts::FRAC_PI_2;
+///
+/// use vexide::devices::math::{Pose2d, Transform2d, Vec2};
+///
+/// // Facing along the y-axis at (1, 1).
+/// let pose = Pose2d::new(1.0, 1.0, FRAC_PI_2);
+///
+/// // Driving forwards 2 units in the robot's frame moves along the field's y-axis.
+/// let moved = pose + Transform2d::new(Vec2::new(2.0, 0.0), 0.0);
+/// assert!((moved.position() - Vec2::new(1.0, 3.0)).length() < 1e-9);
+///
+/// // The transform between two poses takes the first to the second.
+/// let transform = moved - pose;
+/// assert!((transform.translation - Vec2::new(2.0, 0.0)).length() < 1e-9);
+/// ```
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct Pose2d {
+    /// The x coordinate of the pose.
+    pub x: f64,
+
+    /// The y coordinate of the pose.
+    pub y: f64,
+
+    /// The heading of the pose in radians, counterclockwise from the positive x-axis.
+    pub heading: f64,
+}
+
+impl Pose2d {
+    /// The pose at the origin, facing along the positive x-axis.
+    pub const ORIGIN: Self = Self::new(0.0, 0.0, 0.0);
+
+    /// Creates a new pose.
+    #[must_use]
+    pub const fn new(x: f64, y: f64, heading: f64) -> Self {
+        Self { x, y, heading }
+    }
+
+    /// Creates a pose at `position` with the given heading.
+    #[must_use]
+    pub const fn from_position(position: Vec2, heading: f64) -> Self {
+        Self::new(position.x, position.y, heading)
+    }
+
+    /// Creates a pose from a sensor's position and Euler angles, such as those returned by
+    /// [`GpsSensor::position`] and [`InertialSensor::euler`].
+    ///
+    /// VEX sensors report yaw (the `b` angle) clockwise, so it is negated to give a
+    /// counterclockwise heading.
+    ///
+    /// [`GpsSensor::position`]: crate::smart::gps::GpsSensor::position
+    /// [`InertialSensor::euler`]: crate::smart::imu::InertialSensor::euler
+    #[must_use]
+    pub fn from_sensors(position: Point2<f64>, euler: EulerAngles<f64, f64>) -> Self {
+        Self::new(position.x, position.y, wrap_angle(-euler.b))
+    }
+
+    /// Returns the position of the pose.
+    #[must_use]
+    pub const fn position(&self) -> Vec2 {
+        Vec2::new(self.x, self.y)
+    }
+
+    /// Returns a unit vector pointing in the direction of the pose's heading.
+    #[must_use]
+    pub fn direction(&self) -> Vec2 {
+        Vec2::from_polar(1.0, self.heading)
+    }
+
+    /// Applies a transform in this pose's frame, returning the resulting pose.
+    ///
+    /// The transform's translation is relative to the direction this pose is facing. This is
+    /// the same as `self + transform`.
+    #[must_use]
+    pub fn transform_by(&self, transform: Transform2d) -> Self {
+        Self::from_position(
+            self.position() + transform.translation.rotated(self.heading),
+            wrap_angle(self.heading + transform.rotation),
+        )
+    }
+
+    /// Composes two poses, treating `other` as relative to this pose.
+    #[must_use]
+    pub fn compose(&self, other: Self) -> Self {
+        self.transform_by(Transform2d::new(other.position(), other.heading))
+    }
+
+    /// Returns this pose expressed relative to `origin`.
+    ///
+    /// This is the inverse of [`Pose2d::compose`]: `origin.compose(pose.relative_to(origin))`
+    /// is `pose`.
+    #[must_use]
+    pub fn relative_to(&self, origin: Self) -> Self {
+        origin.inverse().compose(*self)
+    }
+
+    /// Returns the inverse of this pose, such that composing the two gives the origin.
+    #[must_use]
+    pub fn inverse(&self) -> Self {
+        Self::from_position(
+            (-self.position()).rotated(-self.heading),
+            wrap_angle(-self.heading),
+        )
+    }
+}
+
+impl Add<Transform2d> for Pose2d {
+    type Output = Self;
+
+    fn add(self, rhs: Transform2d) -> Self::Output {
+        self.transform_by(rhs)
+    }
+}
+
+/// Returns the transform that takes `rhs` to `self`.
+impl Sub<Pose2d> for Pose2d {
+    type Output = Transform2d;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Transform2d::between(rhs, self)
+    }
+}
+
+impl From<Pose2d> for Point2<f64> {
+    fn from(pose: Pose2d) -> Self {
+        pose.position().into()
+    }
+}
+
+/// A rigid transformation, made up of a translation followed by a rotation.
+///
+/// Transforms describe motion relative to a pose, so the translation is in the frame of the
+/// pose being transformed: a translation of `(1, 0)` always moves one unit forwards.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct Transform2d {
+    /// The translation of the transform.
+    pub translation: Vec2,
+
+    /// The rotation of the transform in radians, counterclockwise.
+    pub rotation: f64,
+}
+
+impl Transform2d {
+    /// The transform that does nothing.
+    pub const IDENTITY: Self = Self::new(Vec2::ZERO, 0.0);
+
+    /// Creates a new transform.
+    #[must_use]
+    pub const fn new(translation: Vec2, rotation: f64) -> Self {
+        Self {
+            translation,
+            rotation,
+        }
+    }
+
+    /// Returns the transform that takes `from` to `to`.
+    #[must_use]
+    pub fn between(from: Pose2d, to: Pose2d) -> Self {
+        let relative = to.relative_to(from);
+        Self::new(relative.position(), relative.heading)
+    }
+
+    /// Returns the transform that undoes this one.
+    #[must_use]
+    pub fn inverse(&self) -> Self {
+        Self::new(
+            (-self.translation).rotated(-self.rotation),
+            wrap_angle(-self.rotation),
+        )
+    }
+
+    /// Returns the transform that applies this transform and then `other`.
+    #[must_use]
+    pub fn then(&self, other: Self) -> Self {
+        Self::new(
+            self.translation + other.translation.rotated(self.rotation),
+            wrap_angle(self.rotation + other.rotation),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::{
+        f64::consts::{FRAC_PI_2, PI},
+        marker::PhantomData,
+    };
+
+    use super::*;
+    use crate::math::angle_difference;
+
+    const EPSILON: f64 = 1e-9;
+
+    const POSES: [Pose2d; 4] = [
+        Pose2d::ORIGIN,
+        Pose2d::new(1.0, 2.0, 0.5),
+        Pose2d::new(-3.0, 0.5, -2.5),
+        Pose2d::new(2.0, -4.0, PI - 0.1),
+    ];
+
+    const TRANSFORMS: [Transform2d; 3] = [
+        Transform2d::new(Vec2::new(2.0, 0.0), FRAC_PI_2),
+        Transform2d::new(Vec2::new(-1.0, 3.0), -1.0),
+        Transform2d::new(Vec2::new(0.5, -0.5), PI - 0.2),
+    ];
+
+    fn assert_pose_close(actual: Pose2d, expected: Pose2d) {
+        assert!(
+            actual.position().distance(expected.position()) < EPSILON
+                && angle_difference(actual.heading, expected.heading).abs() < EPSILON,
+            "expected {expected:?}, got {actual:?}"
+        );
+    }
+
+    fn assert_transform_close(actual: Transform2d, expected: Transform2d) {
+        assert!(
+            actual.translation.distance(expected.translation) < EPSILON
+                && angle_difference(actual.rotation, expected.rotation).abs() < EPSILON,
+            "expected {expected:?}, got {actual:?}"
+        );
+    }
+
+    #[test]
+    fn compose_moves_in_local_frame() {
+        let pose = Pose2d::new(1.0, 1.0, FRAC_PI_2);
+
+        assert_pose_close(
+            pose.compose(Pose2d::new(2.0, 0.0, FRAC_PI_2)),
+            Pose2d::new(1.0, 3.0, PI),
+        );
+    }
+
+    #[test]
+    fn compose_with_inverse_is_origin() {
+        for pose in POSES {
+            assert_pose_close(pose.compose(pose.inverse()), Pose2d::ORIGIN);
+            assert_pose_close(pose.inverse().compose(pose), Pose2d::ORIGIN);
+        }
+    }
+
+    #[test]
+    fn relative_to_round_trips() {
+        for a in POSES {
+            for b in POSES {
+                assert_pose_close(a.compose(b.relative_to(a)), b);
+                assert_pose_close(a + (b - a), b);
+            }
+        }
+    }
+
+    #[test]
+    fn then_applies_in_order() {
+        for pose in POSES {
+            for first in TRANSFORMS {
+                for second in TRANSFORMS {
+                    assert_pose_close(pose + first.then(second), pose + first + second);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn transform_inverse_undoes_transform() {
+        for transform in TRANSFORMS {
+            assert_transform_close(transform.then(transform.inverse()), Transform2d::IDENTITY);
+            assert_transform_close(transform.inverse().then(transform), Transform2d::IDENTITY);
+
+            for pose in POSES {
+                assert_pose_close(pose + transform + transform.inverse(), pose);
+            }
+        }
+    }
+
+    #[test]
+    fn from_sensors_converts_clockwise_yaw() {
+        let euler = |yaw| EulerAngles {
+            a: 0.1,
+            b: yaw,
+            c: -0.2,
+            marker: PhantomData,
+        };
+        let position = Point2 { x: 1.0, y: -2.0 };
+
+        assert_pose_close(
+            Pose2d::from_sensors(position, euler(FRAC_PI_2)),
+            Pose2d::new(1.0, -2.0, -FRAC_PI_2),
+        );
+        assert_pose_close(
+            Pose2d::from_sensors(position, euler(3.0 * FRAC_PI_2)),
+            Pose2d::new(1.0, -2.0, FRAC_PI_2),
+        );
+    }
+}
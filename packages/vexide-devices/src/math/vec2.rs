@@ -0,0 +1,264 @@
+//! Two-dimensional vectors.
+
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use mint::{Point2, Vector2};
+use vexide_core::float::Float;
+
+/// A two-dimensional vector.
+///
+/// Angles are in radians, measured counterclockwise from the positive x-axis.
+///
+/// # Examples
+///
+/// ```
+/// use core::f64::consts::FRAC_PI_2;
+///
+/// use vexide::devices::math::Vec2;
+///
+/// let forward = Vec2::new(1.0, 0.0);
+/// let left = forward.rotated(FRAC_PI_2);
+///
+/// assert!((left - Vec2::new(0.0, 1.0)).length() < 1e-9);
+/// assert!(forward.cross(left) > 0.0);
+/// ```
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct Vec2 {
+    /// The x component of the vector.
+    pub x: f64,
+
+    /// The y component of the vector.
+    pub y: f64,
+}
+
+impl Vec2 {
+    /// The zero vector.
+    pub const ZERO: Self = Self::new(0.0, 0.0);
+
+    /// Creates a new vector.
+    #[must_use]
+    pub const fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+
+    /// Creates a vector with the given length, pointing at `angle` radians.
+    #[must_use]
+    pub fn from_polar(length: f64, angle: f64) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self::new(length * cos, length * sin)
+    }
+
+    /// Returns the length of the vector.
+    #[must_use]
+    pub fn length(&self) -> f64 {
+        self.x.hypot(self.y)
+    }
+
+    /// Returns the squared length of the vector.
+    ///
+    /// This is cheaper to compute than [`Vec2::length`], and is sufficient for comparing
+    /// lengths.
+    #[must_use]
+    pub const fn length_squared(&self) -> f64 {
+        self.dot(*self)
+    }
+
+    /// Returns the angle of the vector in radians, in the range `[-π, π]`.
+    #[must_use]
+    pub fn angle(&self) -> f64 {
+        self.y.atan2(self.x)
+    }
+
+    /// Returns the distance between the points represented by two vectors.
+    #[must_use]
+    pub fn distance(&self, other: Self) -> f64 {
+        (other - *self).length()
+    }
+
+    /// Returns the dot product of two vectors.
+    #[must_use]
+    pub const fn dot(&self, other: Self) -> f64 {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// Returns the z component of the cross product of two vectors.
+    ///
+    /// This is positive if `other` is counterclockwise from this vector, and negative if it
+    /// is clockwise.
+    #[must_use]
+    pub const fn cross(&self, other: Self) -> f64 {
+        self.x * other.y - self.y * other.x
+    }
+
+    /// Returns the vector rotated counterclockwise by `angle` radians.
+    #[must_use]
+    pub fn rotated(&self, angle: f64) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+    }
+
+    /// Returns the vector rotated counterclockwise by 90 degrees.
+    #[must_use]
+    pub const fn perpendicular(&self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+
+    /// Returns a vector with the same direction and a length of 1.
+    ///
+    /// Returns `None` if the vector has no length.
+    #[must_use]
+    pub fn normalized(&self) -> Option<Self> {
+        let length = self.length();
+        (length > 0.0).then(|| *self / length)
+    }
+
+    /// Linearly interpolates between two vectors, returning `self` when `t` is 0.0 and `other`
+    /// when `t` is 1.0.
+    #[must_use]
+    pub fn lerp(&self, other: Self, t: f64) -> Self {
+        *self + (other - *self) * t
+    }
+}
+
+impl Add<Vec2> for Vec2 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl Sub<Vec2> for Vec2 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Mul<f64> for Vec2 {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        Self::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Mul<Vec2> for f64 {
+    type Output = Vec2;
+
+    fn mul(self, rhs: Vec2) -> Self::Output {
+        rhs * self
+    }
+}
+
+impl Div<f64> for Vec2 {
+    type Output = Self;
+
+    fn div(self, rhs: f64) -> Self::Output {
+        Self::new(self.x / rhs, self.y / rhs)
+    }
+}
+
+impl AddAssign<Vec2> for Vec2 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign<Vec2> for Vec2 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign<f64> for Vec2 {
+    fn mul_assign(&mut self, rhs: f64) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign<f64> for Vec2 {
+    fn div_assign(&mut self, rhs: f64) {
+        *self = *self / rhs;
+    }
+}
+
+impl Neg for Vec2 {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self::new(-self.x, -self.y)
+    }
+}
+
+impl From<Point2<f64>> for Vec2 {
+    fn from(point: Point2<f64>) -> Self {
+        Self::new(point.x, point.y)
+    }
+}
+
+impl From<Vec2> for Point2<f64> {
+    fn from(vec: Vec2) -> Self {
+        Self { x: vec.x, y: vec.y }
+    }
+}
+
+impl From<Vector2<f64>> for Vec2 {
+    fn from(vector: Vector2<f64>) -> Self {
+        Self::new(vector.x, vector.y)
+    }
+}
+
+impl From<Vec2> for Vector2<f64> {
+    fn from(vec: Vec2) -> Self {
+        Self { x: vec.x, y: vec.y }
+    }
+}
+
+impl From<(f64, f64)> for Vec2 {
+    fn from((x, y): (f64, f64)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::f64::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    use super::*;
+
+    const EPSILON: f64 = 1e-9;
+
+    fn assert_vec_close(actual: Vec2, expected: Vec2) {
+        assert!(
+            actual.distance(expected) < EPSILON,
+            "expected {expected:?}, got {actual:?}"
+        );
+    }
+
+    #[test]
+    fn rotated_is_counterclockwise() {
+        assert_vec_close(Vec2::new(1.0, 0.0).rotated(FRAC_PI_2), Vec2::new(0.0, 1.0));
+        assert_vec_close(Vec2::new(1.0, 2.0).rotated(PI), Vec2::new(-1.0, -2.0));
+        assert_vec_close(Vec2::new(0.0, 1.0).rotated(-FRAC_PI_2), Vec2::new(1.0, 0.0));
+    }
+
+    #[test]
+    fn polar_round_trip() {
+        let vec = Vec2::from_polar(2.0, 3.0 * FRAC_PI_4);
+
+        assert_vec_close(vec, Vec2::new(-2.0_f64.sqrt(), 2.0_f64.sqrt()));
+        assert!((vec.length() - 2.0).abs() < EPSILON);
+        assert!((vec.angle() - 3.0 * FRAC_PI_4).abs() < EPSILON);
+    }
+
+    #[test]
+    fn normalized() {
+        assert_vec_close(
+            Vec2::new(3.0, -4.0).normalized().unwrap(),
+            Vec2::new(0.6, -0.8),
+        );
+        assert_eq!(Vec2::ZERO.normalized(), None);
+    }
+}
@@ -0,0 +1,567 @@
+//! Wheel Odometry
+//!
+//! Odometry estimates a robot's position on the field by adding up how far it has moved since
+//! it started. This module tracks a robot's [`Pose2d`] using unpowered tracking wheels, which
+//! measure distance travelled, and an [`InertialSensor`], which measures heading.
+//!
+//! Tracking wheels are mounted either _parallel_ to the robot's direction of travel, measuring
+//! how far it drives forwards, or _perpendicular_ to it, measuring how far it slides sideways.
+//! At least one parallel wheel is needed. Without a perpendicular wheel, the robot is assumed
+//! not to slide sideways.
+//!
+//! Each update, the change in heading and wheel distances is integrated assuming the robot
+//! moved along a circular arc, which is exact for a robot driving at constant speeds and far
+//! more accurate than assuming straight-line motion between updates.
+//!
+//! # Coordinates
+//!
+//! Poses follow the conventions of the [`math`](crate::math) module: positions are in meters,
+//! and headings are in radians, measured counterclockwise from the positive x-axis. In the
+//! robot's own frame, x points forwards and y points to the left.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use vexide::{
+//!     devices::{
+//!         math::Pose2d,
+//!         odometry::{Odometry, TrackingWheel},
+//!         position::Length,
+//!     },
+//!     prelude::*,
+//! };
+//!
+//! #[vexide::main]
+//! async fn main(peripherals: Peripherals) {
+//!     let mut imu = InertialSensor::new(peripherals.port_1);
+//!     _ = imu.calibrate().await;
+//!
+//!     let odometry = Odometry::new(
+//!         imu,
+//!         [TrackingWheel::new(
+//!             RotationSensor::new(peripherals.port_2, Direction::Forward),
+//!             Length::from_inches(2.75),
+//!             Length::from_inches(-1.5),
+//!         )],
+//!         [TrackingWheel::new(
+//!             RotationSensor::new(peripherals.port_3, Direction::Forward),
+//!             Length::from_inches(2.75),
+//!             Length::from_inches(-4.0),
+//!         )],
+//!         Pose2d::ORIGIN,
+//!     );
+//!
+//!     // Run odometry in the background, and read its estimate from another task.
+//!     let tracker = odometry.tracker();
+//!     let _task = spawn(odometry.run());
+//!
+//!     loop {
+//!         let pose = tracker.pose();
+//!         println!("x: {}m, y: {}m, heading: {}rad", pose.x, pose.y, pose.heading);
+//!
+//!         sleep(core::time::Duration::from_millis(100)).await;
+//!     }
+//! }
+//! ```
+
+use alloc::{boxed::Box, sync::Arc, vec, vec::Vec};
+use core::{
+    f64::consts::PI,
+    sync::atomic::{AtomicU32, AtomicU64, Ordering},
+    time::Duration,
+};
+
+use vexide_core::float::Float;
+
+use crate::{
+    adi::{encoder::AdiEncoder, AdiDevice},
+    control::Interval,
+    math::{wrap_angle, Pose2d, Vec2},
+    position::{Length, Position},
+    smart::{imu::InertialSensor, rotation::RotationSensor, SmartDevice},
+    PortError,
+};
+
+/// A sensor that measures the rotation of a tracking wheel.
+pub trait TrackingSensor {
+    /// Returns the total rotation of the wheel.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the sensor couldn't be read.
+    fn position(&self) -> Result<Position, PortError>;
+
+    /// Returns the interval at which the sensor's readings are updated.
+    fn update_interval(&self) -> Duration;
+}
+
+impl TrackingSensor for RotationSensor {
+    fn position(&self) -> Result<Position, PortError> {
+        self.position()
+    }
+
+    fn update_interval(&self) -> Duration {
+        <Self as SmartDevice>::UPDATE_INTERVAL
+    }
+}
+
+impl TrackingSensor for AdiEncoder {
+    fn position(&self) -> Result<Position, PortError> {
+        self.position()
+    }
+
+    fn update_interval(&self) -> Duration {
+        <Self as AdiDevice<2>>::UPDATE_INTERVAL
+    }
+}
+
+/// An unpowered wheel that measures how far the robot has travelled.
+pub struct TrackingWheel {
+    sensor: Box<dyn TrackingSensor>,
+    diameter: Length,
+    offset: Length,
+}
+
+impl TrackingWheel {
+    /// Creates a new tracking wheel.
+    ///
+    /// The sensor should read positive values when the wheel rolls forwards (for parallel
+    /// wheels) or to the left (for perpendicular wheels).
+    ///
+    /// `offset` is the wheel's distance from the robot's tracking center, perpendicular to the
+    /// direction the wheel rolls. For parallel wheels, this is positive when the wheel is to
+    /// the left of the tracking center. For perpendicular wheels, this is positive when the
+    /// wheel is in front of the tracking center.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `diameter` is not finite and positive.
+    #[must_use]
+    pub fn new(sensor: impl TrackingSensor + 'static, diameter: Length, offset: Length) -> Self {
+        assert!(
+            diameter.as_meters().is_finite() && diameter.as_meters() > 0.0,
+            "tracking wheel diameter must be finite and positive"
+        );
+
+        Self {
+            sensor: Box::new(sensor),
+            diameter,
+            offset,
+        }
+    }
+
+    /// Returns the diameter of the wheel.
+    #[must_use]
+    pub const fn diameter(&self) -> Length {
+        self.diameter
+    }
+
+    /// Returns the offset of the wheel from the robot's tracking center.
+    #[must_use]
+    pub const fn offset(&self) -> Length {
+        self.offset
+    }
+
+    /// Returns the total distance rolled by the wheel in meters.
+    fn distance(&self) -> Result<f64, PortError> {
+        Ok(self.sensor.position()?.as_revolutions() * PI * self.diameter.as_meters())
+    }
+}
+
+impl core::fmt::Debug for TrackingWheel {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TrackingWheel")
+            .field("diameter", &self.diameter)
+            .field("offset", &self.offset)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A pose shared between the odometry task and its [`OdometryTracker`]s.
+///
+/// The pose is stored in atomics behind a sequence counter, so that it can be read and written
+/// from any task without locking, and a read never observes a partially-written pose.
+#[derive(Debug, Default)]
+struct SharedPose {
+    sequence: AtomicU32,
+    x: AtomicU64,
+    y: AtomicU64,
+    heading: AtomicU64,
+}
+
+impl SharedPose {
+    fn new(pose: Pose2d) -> Self {
+        let shared = Self::default();
+        shared.store(pose);
+        shared
+    }
+
+    fn load(&self) -> Pose2d {
+        loop {
+            let sequence = self.sequence.load(Ordering::Acquire);
+            if sequence % 2 == 1 {
+                // A write is in progress.
+                core::hint::spin_loop();
+                continue;
+            }
+
+            let pose = Pose2d::new(
+                f64::from_bits(self.x.load(Ordering::Acquire)),
+                f64::from_bits(self.y.load(Ordering::Acquire)),
+                f64::from_bits(self.heading.load(Ordering::Acquire)),
+            );
+
+            if self.sequence.load(Ordering::Acquire) == sequence {
+                return pose;
+            }
+        }
+    }
+
+    fn store(&self, pose: Pose2d) {
+        self.sequence.fetch_add(1, Ordering::AcqRel);
+        self.x.store(pose.x.to_bits(), Ordering::Release);
+        self.y.store(pose.y.to_bits(), Ordering::Release);
+        self.heading
+            .store(pose.heading.to_bits(), Ordering::Release);
+        self.sequence.fetch_add(1, Ordering::AcqRel);
+    }
+}
+
+/// A cloneable handle for reading and resetting an [`Odometry`] pose estimate from other
+/// tasks.
+///
+/// Reading the pose never waits for the odometry task, so it is safe to call from
+/// time-sensitive code such as motion controllers.
+#[derive(Debug, Clone)]
+pub struct OdometryTracker {
+    pose: Arc<SharedPose>,
+}
+
+impl OdometryTracker {
+    /// Returns the latest estimate of the robot's pose.
+    #[must_use]
+    pub fn pose(&self) -> Pose2d {
+        self.pose.load()
+    }
+
+    /// Overwrites the pose estimate, such as when the robot is placed at a known position.
+    ///
+    /// Motion after this call is tracked relative to the new pose.
+    pub fn set_pose(&self, pose: Pose2d) {
+        self.pose.store(pose);
+    }
+}
+
+/// Estimates a robot's pose from tracking wheels and an inertial sensor.
+///
+/// See the [module-level documentation](self) for more information.
+#[derive(Debug)]
+pub struct Odometry {
+    imu: InertialSensor,
+    parallel: Vec<TrackingWheel>,
+    perpendicular: Vec<TrackingWheel>,
+    pose: Arc<SharedPose>,
+
+    /// The heading from the previous update, or `None` before the first update.
+    previous_heading: Option<f64>,
+
+    // Wheel distances from the previous and current updates, with parallel wheels first.
+    // These are allocated up front so that updates don't need to allocate.
+    previous: Vec<f64>,
+    current: Vec<f64>,
+}
+
+impl Odometry {
+    /// Creates a new odometry system starting at `initial_pose`.
+    ///
+    /// The inertial sensor should already be calibrated. Readings from several wheels of the
+    /// same kind are averaged.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if no parallel tracking wheels are given.
+    #[must_use]
+    pub fn new(
+        imu: InertialSensor,
+        parallel: impl IntoIterator<Item = TrackingWheel>,
+        perpendicular: impl IntoIterator<Item = TrackingWheel>,
+        initial_pose: Pose2d,
+    ) -> Self {
+        let parallel: Vec<_> = parallel.into_iter().collect();
+        assert!(
+            !parallel.is_empty(),
+            "odometry requires at least one parallel tracking wheel"
+        );
+
+        let perpendicular: Vec<_> = perpendicular.into_iter().collect();
+        let wheel_count = parallel.len() + perpendicular.len();
+
+        Self {
+            imu,
+            parallel,
+            perpendicular,
+            pose: Arc::new(SharedPose::new(initial_pose)),
+            previous_heading: None,
+            previous: vec![0.0; wheel_count],
+            current: vec![0.0; wheel_count],
+        }
+    }
+
+    /// Returns a handle for reading the pose estimate from other tasks.
+    #[must_use]
+    pub fn tracker(&self) -> OdometryTracker {
+        OdometryTracker {
+            pose: Arc::clone(&self.pose),
+        }
+    }
+
+    /// Returns the latest estimate of the robot's pose.
+    #[must_use]
+    pub fn pose(&self) -> Pose2d {
+        self.pose.load()
+    }
+
+    /// Overwrites the pose estimate.
+    ///
+    /// See [`OdometryTracker::set_pose`] for more information.
+    pub fn set_pose(&self, pose: Pose2d) {
+        self.pose.store(pose);
+    }
+
+    /// Returns the interval at which the odometry's sensors are updated.
+    ///
+    /// This is the longest update interval of any of its sensors, since updating any faster
+    /// would only read stale values.
+    #[must_use]
+    pub fn update_interval(&self) -> Duration {
+        self.parallel
+            .iter()
+            .chain(&self.perpendicular)
+            .map(|wheel| wheel.sensor.update_interval())
+            .fold(InertialSensor::UPDATE_INTERVAL, Duration::max)
+    }
+
+    /// Reads the sensors and integrates the robot's motion since the previous update.
+    ///
+    /// This only needs to be called directly if odometry is being updated as part of another
+    /// loop; otherwise, use [`Odometry::run`].
+    ///
+    /// If any of the sensors can't be read (for example, because the inertial sensor is still
+    /// calibrating or a sensor has been unplugged), the update is skipped and the robot's
+    /// motion is instead picked up by the next update that succeeds.
+    pub fn update(&mut self) {
+        // The inertial sensor measures rotation clockwise in degrees.
+        let Ok(rotation) = self.imu.rotation() else {
+            return;
+        };
+        let heading = -rotation.to_radians();
+
+        let wheels = self.parallel.iter().chain(&self.perpendicular);
+        for (wheel, distance) in wheels.zip(&mut self.current) {
+            let Ok(reading) = wheel.distance() else {
+                return;
+            };
+            *distance = reading;
+        }
+
+        if let Some(previous_heading) = self.previous_heading {
+            // Replace the previous distances with the change since then.
+            for (previous, current) in self.previous.iter_mut().zip(&self.current) {
+                *previous = current - *previous;
+            }
+            let (parallel_deltas, perpendicular_deltas) =
+                self.previous.split_at(self.parallel.len());
+
+            let pose = self.pose.load();
+            self.pose.store(integrate(
+                pose,
+                heading - previous_heading,
+                &self.parallel,
+                parallel_deltas,
+                &self.perpendicular,
+                perpendicular_deltas,
+            ));
+        }
+
+        self.previous_heading = Some(heading);
+        core::mem::swap(&mut self.previous, &mut self.current);
+    }
+
+    /// Runs odometry forever, updating the pose estimate once every
+    /// [update interval](Odometry::update_interval).
+    ///
+    /// This is meant to be spawned as a background task. Use a [tracker](Odometry::tracker) to
+    /// read the pose estimate while it runs.
+    pub async fn run(mut self) {
+        let mut interval = Interval::new(self.update_interval());
+
+        loop {
+            self.update();
+            interval.tick().await;
+        }
+    }
+}
+
+/// Returns the mean distance travelled by the tracking center along the wheels' axis,
+/// correcting each wheel for its offset.
+///
+/// `sign` is the direction in which a wheel's offset causes it to move when the robot turns
+/// counterclockwise.
+#[allow(clippy::cast_precision_loss)]
+fn center_travel(wheels: &[TrackingWheel], deltas: &[f64], dtheta: f64, sign: f64) -> f64 {
+    if wheels.is_empty() {
+        return 0.0;
+    }
+
+    let total: f64 = wheels
+        .iter()
+        .zip(deltas)
+        .map(|(wheel, delta)| {
+            let offset = wheel.offset.as_meters();
+
+            if dtheta.abs() < 1e-9 {
+                *delta
+            } else {
+                // Each wheel travels along an arc about the same center of rotation as the
+                // tracking center. The radius of the tracking center's arc is the wheel's
+                // radius adjusted by its offset, and the length of the chord across it is the
+                // distance actually travelled.
+                let radius = delta / dtheta - sign * offset;
+                2.0 * (dtheta / 2.0).sin() * radius
+            }
+        })
+        .sum();
+
+    total / wheels.len() as f64
+}
+
+/// Integrates the robot's motion over one update as an arc.
+fn integrate(
+    pose: Pose2d,
+    dtheta: f64,
+    parallel: &[TrackingWheel],
+    parallel_deltas: &[f64],
+    perpendicular: &[TrackingWheel],
+    perpendicular_deltas: &[f64],
+) -> Pose2d {
+    // When turning counterclockwise, wheels to the left of the tracking center roll backwards
+    // and wheels in front of it roll to the left.
+    let forward = center_travel(parallel, parallel_deltas, dtheta, -1.0);
+    let sideways = center_travel(perpendicular, perpendicular_deltas, dtheta, 1.0);
+
+    // The chord of the arc points along the average heading over the update.
+    let displacement = Vec2::new(forward, sideways).rotated(pose.heading + dtheta / 2.0);
+
+    Pose2d::from_position(
+        pose.position() + displacement,
+        wrap_angle(pose.heading + dtheta),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use core::f64::consts::{FRAC_PI_2, SQRT_2};
+
+    use super::*;
+
+    const EPSILON: f64 = 1e-9;
+
+    fn assert_pose(actual: Pose2d, expected: Pose2d) {
+        assert!(
+            (actual.position() - expected.position()).length() < EPSILON
+                && (actual.heading - expected.heading).abs() < EPSILON,
+            "expected {expected:?}, got {actual:?}"
+        );
+    }
+
+    /// A sensor for wheels that are never read.
+    struct UnusedSensor;
+
+    impl TrackingSensor for UnusedSensor {
+        fn position(&self) -> Result<Position, PortError> {
+            unreachable!()
+        }
+
+        fn update_interval(&self) -> Duration {
+            Duration::from_millis(10)
+        }
+    }
+
+    fn wheel(offset: f64) -> TrackingWheel {
+        TrackingWheel::new(
+            UnusedSensor,
+            Length::from_meters(0.07),
+            Length::from_meters(offset),
+        )
+    }
+
+    #[test]
+    fn straight_travel() {
+        let start = Pose2d::new(1.0, 2.0, FRAC_PI_2);
+
+        // Wheel offsets don't matter when the robot isn't turning.
+        let pose = integrate(
+            start,
+            0.0,
+            &[wheel(0.1), wheel(-0.1)],
+            &[0.5, 0.5],
+            &[],
+            &[],
+        );
+        assert_pose(pose, Pose2d::new(1.0, 2.5, FRAC_PI_2));
+
+        let pose = integrate(start, 0.0, &[wheel(0.1)], &[0.5], &[wheel(0.2)], &[0.25]);
+        assert_pose(pose, Pose2d::new(0.75, 2.5, FRAC_PI_2));
+    }
+
+    #[test]
+    fn pure_turn_with_offset_wheels() {
+        let start = Pose2d::new(1.0, 2.0, 0.0);
+        let dtheta = FRAC_PI_2;
+
+        // Turning counterclockwise in place, a wheel to the left of the tracking center rolls
+        // backwards and a wheel in front of it rolls to the left.
+        let parallel = [wheel(0.1)];
+        let perpendicular = [wheel(0.2)];
+        let parallel_deltas = [-0.1 * dtheta];
+        let perpendicular_deltas = [0.2 * dtheta];
+
+        assert!(center_travel(&parallel, &parallel_deltas, dtheta, -1.0).abs() < EPSILON);
+        assert!(center_travel(&perpendicular, &perpendicular_deltas, dtheta, 1.0).abs() < EPSILON);
+
+        let pose = integrate(
+            start,
+            dtheta,
+            &parallel,
+            &parallel_deltas,
+            &perpendicular,
+            &perpendicular_deltas,
+        );
+        assert_pose(pose, Pose2d::new(1.0, 2.0, FRAC_PI_2));
+    }
+
+    #[test]
+    fn arc() {
+        // A quarter circle with a radius of 1 meter, turning left about (0, 1).
+        let dtheta = FRAC_PI_2;
+        let parallel = [wheel(0.2), wheel(-0.2)];
+        let parallel_deltas = [0.8 * dtheta, 1.2 * dtheta];
+        let perpendicular = [wheel(0.1)];
+        let perpendicular_deltas = [0.1 * dtheta];
+
+        // The tracking center travels along the chord of the arc.
+        assert!(
+            (center_travel(&parallel, &parallel_deltas, dtheta, -1.0) - SQRT_2).abs() < EPSILON
+        );
+
+        let pose = integrate(
+            Pose2d::ORIGIN,
+            dtheta,
+            &parallel,
+            &parallel_deltas,
+            &perpendicular,
+            &perpendicular_deltas,
+        );
+        assert_pose(pose, Pose2d::new(1.0, 1.0, FRAC_PI_2));
+    }
+}
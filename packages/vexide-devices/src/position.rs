@@ -1,10 +1,27 @@
-//! Angular Position Type
+//! Measurement Types
 //!
-//! Standard return type for sensors measuring rotational position
+//! Standard return types for sensors measuring rotational position, angular velocity, and
+//! distance.
+//!
+//! Device getters historically return bare `f64`s in whichever unit the device reports, such
+//! as RPM from [`Motor::velocity`] and degrees per second from [`RotationSensor::velocity`].
+//! The types in this module carry their unit with them, so they can be mixed freely:
+//!
+//! - [`Position`] is an angle.
+//! - [`AngularVelocity`] is a rate of rotation.
+//! - [`Length`] is a linear distance.
+//! - [`Velocity`] is a linear speed.
+//!
+//! Dividing a [`Position`] or [`Length`] by a [`Duration`] gives an [`AngularVelocity`] or
+//! [`Velocity`], and multiplying those by a [`Duration`] gives a [`Position`] or [`Length`] back.
+//!
+//! [`Motor::velocity`]: crate::smart::motor::Motor::velocity
+//! [`RotationSensor::velocity`]: crate::smart::rotation::RotationSensor::velocity
 
 use core::{
     f64::consts::TAU,
     ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+    time::Duration,
 };
 
 /// A opaque fixed-point raw angular position reading from a sensor.
@@ -136,6 +153,44 @@ impl DivAssign<i64> for Position {
     }
 }
 
+impl Mul<f64> for Position {
+    type Output = Self;
+
+    #[allow(clippy::cast_precision_loss)]
+    fn mul(self, rhs: f64) -> Self::Output {
+        Self((self.0 as f64 * rhs) as i64)
+    }
+}
+
+impl Div<f64> for Position {
+    type Output = Self;
+
+    #[allow(clippy::cast_precision_loss)]
+    fn div(self, rhs: f64) -> Self::Output {
+        Self((self.0 as f64 / rhs) as i64)
+    }
+}
+
+impl MulAssign<f64> for Position {
+    fn mul_assign(&mut self, rhs: f64) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign<f64> for Position {
+    fn div_assign(&mut self, rhs: f64) {
+        *self = *self / rhs;
+    }
+}
+
+impl Div<Duration> for Position {
+    type Output = AngularVelocity;
+
+    fn div(self, rhs: Duration) -> Self::Output {
+        AngularVelocity::from_radians_per_second(self.as_radians() / rhs.as_secs_f64())
+    }
+}
+
 impl Neg for Position {
     type Output = Self;
 
@@ -143,3 +198,338 @@ impl Neg for Position {
         Self(-self.0)
     }
 }
+
+/// Implements arithmetic for a measurement type wrapping an `f64`.
+macro_rules! impl_unit_ops {
+    ($unit:ident) => {
+        impl Add<$unit> for $unit {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self::Output {
+                Self(self.0 + rhs.0)
+            }
+        }
+
+        impl Sub<$unit> for $unit {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self::Output {
+                Self(self.0 - rhs.0)
+            }
+        }
+
+        impl Mul<f64> for $unit {
+            type Output = Self;
+
+            fn mul(self, rhs: f64) -> Self::Output {
+                Self(self.0 * rhs)
+            }
+        }
+
+        impl Mul<$unit> for f64 {
+            type Output = $unit;
+
+            fn mul(self, rhs: $unit) -> Self::Output {
+                $unit(self * rhs.0)
+            }
+        }
+
+        impl Div<f64> for $unit {
+            type Output = Self;
+
+            fn div(self, rhs: f64) -> Self::Output {
+                Self(self.0 / rhs)
+            }
+        }
+
+        /// Returns the ratio between two measurements.
+        impl Div<$unit> for $unit {
+            type Output = f64;
+
+            fn div(self, rhs: Self) -> Self::Output {
+                self.0 / rhs.0
+            }
+        }
+
+        impl AddAssign<$unit> for $unit {
+            fn add_assign(&mut self, rhs: Self) {
+                self.0 += rhs.0;
+            }
+        }
+
+        impl SubAssign<$unit> for $unit {
+            fn sub_assign(&mut self, rhs: Self) {
+                self.0 -= rhs.0;
+            }
+        }
+
+        impl MulAssign<f64> for $unit {
+            fn mul_assign(&mut self, rhs: f64) {
+                self.0 *= rhs;
+            }
+        }
+
+        impl DivAssign<f64> for $unit {
+            fn div_assign(&mut self, rhs: f64) {
+                self.0 /= rhs;
+            }
+        }
+
+        impl Neg for $unit {
+            type Output = Self;
+
+            fn neg(self) -> Self::Output {
+                Self(-self.0)
+            }
+        }
+    };
+}
+
+/// A rate of rotation.
+#[derive(Default, Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct AngularVelocity(f64);
+
+impl AngularVelocity {
+    /// Creates an angular velocity from a specified number of revolutions per minute.
+    #[must_use]
+    pub const fn from_rpm(rpm: f64) -> Self {
+        Self(rpm * TAU / 60.0)
+    }
+
+    /// Creates an angular velocity from a specified number of degrees per second.
+    #[must_use]
+    pub const fn from_degrees_per_second(dps: f64) -> Self {
+        Self(dps.to_radians())
+    }
+
+    /// Creates an angular velocity from a specified number of radians per second.
+    #[must_use]
+    pub const fn from_radians_per_second(radians_per_second: f64) -> Self {
+        Self(radians_per_second)
+    }
+
+    /// Returns this angular velocity in revolutions per minute.
+    #[must_use]
+    pub const fn as_rpm(&self) -> f64 {
+        self.0 * 60.0 / TAU
+    }
+
+    /// Returns this angular velocity in degrees per second.
+    #[must_use]
+    pub const fn as_degrees_per_second(&self) -> f64 {
+        self.0.to_degrees()
+    }
+
+    /// Returns this angular velocity in radians per second.
+    #[must_use]
+    pub const fn as_radians_per_second(&self) -> f64 {
+        self.0
+    }
+}
+
+impl_unit_ops!(AngularVelocity);
+
+impl Mul<Duration> for AngularVelocity {
+    type Output = Position;
+
+    fn mul(self, rhs: Duration) -> Self::Output {
+        Position::from_radians(self.0 * rhs.as_secs_f64())
+    }
+}
+
+/// A linear distance.
+#[derive(Default, Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Length(f64);
+
+impl Length {
+    /// The number of meters in an inch.
+    const METERS_PER_INCH: f64 = 0.0254;
+
+    /// Creates a length from a specified number of meters.
+    #[must_use]
+    pub const fn from_meters(meters: f64) -> Self {
+        Self(meters)
+    }
+
+    /// Creates a length from a specified number of centimeters.
+    #[must_use]
+    pub const fn from_centimeters(centimeters: f64) -> Self {
+        Self(centimeters / 100.0)
+    }
+
+    /// Creates a length from a specified number of millimeters.
+    #[must_use]
+    pub const fn from_millimeters(millimeters: f64) -> Self {
+        Self(millimeters / 1000.0)
+    }
+
+    /// Creates a length from a specified number of inches.
+    #[must_use]
+    pub const fn from_inches(inches: f64) -> Self {
+        Self(inches * Self::METERS_PER_INCH)
+    }
+
+    /// Creates a length from a specified number of feet.
+    #[must_use]
+    pub const fn from_feet(feet: f64) -> Self {
+        Self(feet * 12.0 * Self::METERS_PER_INCH)
+    }
+
+    /// Returns this length in meters.
+    #[must_use]
+    pub const fn as_meters(&self) -> f64 {
+        self.0
+    }
+
+    /// Returns this length in centimeters.
+    #[must_use]
+    pub const fn as_centimeters(&self) -> f64 {
+        self.0 * 100.0
+    }
+
+    /// Returns this length in millimeters.
+    #[must_use]
+    pub const fn as_millimeters(&self) -> f64 {
+        self.0 * 1000.0
+    }
+
+    /// Returns this length in inches.
+    #[must_use]
+    pub const fn as_inches(&self) -> f64 {
+        self.0 / Self::METERS_PER_INCH
+    }
+
+    /// Returns this length in feet.
+    #[must_use]
+    pub const fn as_feet(&self) -> f64 {
+        self.0 / Self::METERS_PER_INCH / 12.0
+    }
+}
+
+impl_unit_ops!(Length);
+
+impl Div<Duration> for Length {
+    type Output = Velocity;
+
+    fn div(self, rhs: Duration) -> Self::Output {
+        Velocity(self.0 / rhs.as_secs_f64())
+    }
+}
+
+/// A linear speed.
+#[derive(Default, Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Velocity(f64);
+
+impl Velocity {
+    /// Creates a velocity from a specified number of meters per second.
+    #[must_use]
+    pub const fn from_meters_per_second(meters_per_second: f64) -> Self {
+        Self(meters_per_second)
+    }
+
+    /// Creates a velocity from a specified number of inches per second.
+    #[must_use]
+    pub const fn from_inches_per_second(inches_per_second: f64) -> Self {
+        Self(inches_per_second * Length::METERS_PER_INCH)
+    }
+
+    /// Returns this velocity in meters per second.
+    #[must_use]
+    pub const fn as_meters_per_second(&self) -> f64 {
+        self.0
+    }
+
+    /// Returns this velocity in inches per second.
+    #[must_use]
+    pub const fn as_inches_per_second(&self) -> f64 {
+        self.0 / Length::METERS_PER_INCH
+    }
+}
+
+impl_unit_ops!(Velocity);
+
+impl Mul<Duration> for Velocity {
+    type Output = Length;
+
+    fn mul(self, rhs: Duration) -> Self::Output {
+        Length(self.0 * rhs.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::f64::consts::PI;
+
+    use super::*;
+
+    const EPSILON: f64 = 1e-9;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < EPSILON,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn angular_velocity_conversions() {
+        let velocity = AngularVelocity::from_rpm(60.0);
+        assert_close(velocity.as_degrees_per_second(), 360.0);
+        assert_close(velocity.as_radians_per_second(), TAU);
+
+        let velocity = AngularVelocity::from_degrees_per_second(180.0);
+        assert_close(velocity.as_rpm(), 30.0);
+        assert_close(velocity.as_radians_per_second(), PI);
+
+        let velocity = AngularVelocity::from_radians_per_second(PI / 2.0);
+        assert_close(velocity.as_rpm(), 15.0);
+        assert_close(velocity.as_degrees_per_second(), 90.0);
+    }
+
+    #[test]
+    fn length_conversions() {
+        assert_close(Length::from_inches(1.0).as_meters(), 0.0254);
+        assert_close(Length::from_feet(1.0).as_meters(), 0.3048);
+        assert_close(Length::from_feet(2.0).as_inches(), 24.0);
+        assert_close(Length::from_meters(1.0).as_inches(), 1.0 / 0.0254);
+        assert_close(Length::from_meters(0.6096).as_feet(), 2.0);
+        assert_close(Length::from_millimeters(25.4).as_inches(), 1.0);
+        assert_close(Length::from_centimeters(30.48).as_feet(), 1.0);
+    }
+
+    #[test]
+    fn velocity_conversions() {
+        assert_close(
+            Velocity::from_inches_per_second(10.0).as_meters_per_second(),
+            0.254,
+        );
+        assert_close(
+            Velocity::from_meters_per_second(0.0254).as_inches_per_second(),
+            1.0,
+        );
+    }
+
+    #[test]
+    fn position_duration_round_trip() {
+        let position = Position::from_degrees(90.0);
+        let duration = Duration::from_millis(250);
+
+        let velocity = position / duration;
+        assert_close(velocity.as_degrees_per_second(), 360.0);
+        assert_close(velocity.as_rpm(), 60.0);
+
+        // Positions are fixed-point, so the round trip may be off by a tick.
+        let round_trip = velocity * duration;
+        assert!((round_trip - position).as_degrees().abs() < 1e-3);
+    }
+
+    #[test]
+    fn length_duration_round_trip() {
+        let length = Length::from_feet(3.0);
+        let duration = Duration::from_secs(2);
+
+        let velocity = length / duration;
+        assert_close(velocity.as_inches_per_second(), 18.0);
+        assert_close((velocity * duration).as_feet(), 3.0);
+    }
+}
@@ -26,7 +26,7 @@ use vex_sdk::{
     vexDeviceDistanceObjectVelocityGet, vexDeviceDistanceStatusGet, V5_DeviceT,
 };
 
-use super::{SmartDevice, SmartDeviceType, SmartPort};
+use super::{telemetry::Telemetry, SmartDevice, SmartDeviceType, SmartPort};
 use crate::PortError;
 
 /// A distance sensor plugged into a Smart Port.
@@ -201,6 +201,26 @@ impl SmartDevice for DistanceSensor {
         SmartDeviceType::Distance
     }
 }
+
+impl Telemetry for DistanceSensor {
+    const CHANNELS: &'static [&'static str] = &["distance_mm", "velocity_mps", "confidence"];
+
+    fn read_telemetry(&self, values: &mut [f64]) -> bool {
+        let Ok(object) = self.object() else {
+            return false;
+        };
+
+        // Record NaN while no object is detected, rather than skipping the sample.
+        values.copy_from_slice(&object.map_or([f64::NAN; 3], |object| {
+            [
+                f64::from(object.distance),
+                object.velocity,
+                object.confidence,
+            ]
+        }));
+        true
+    }
+}
 impl From<DistanceSensor> for SmartPort {
     fn from(device: DistanceSensor) -> Self {
         device.port
@@ -32,8 +32,8 @@ use vex_sdk::{
 };
 use vexide_core::float::Float;
 
-use super::{SmartDevice, SmartDeviceType, SmartPort};
-use crate::{math::Point2, PortError};
+use super::{telemetry::Telemetry, SmartDevice, SmartDeviceType, SmartPort};
+use crate::{math::Point2, position::Length, PortError};
 
 /// A GPS sensor plugged into a Smart Port.
 #[derive(Debug, PartialEq)]
@@ -329,6 +329,38 @@ impl GpsSensor {
         Ok(unsafe { vexDeviceGpsErrorGet(self.device) })
     }
 
+    /// Returns the position of the sensor's physical offset on the field.
+    ///
+    /// Each coordinate is a [`Length`] from the center of the field, so it can be read in
+    /// inches or feet with [`Length::as_inches`] and [`Length::as_feet`] rather than only in
+    /// the meters reported by [`GpsSensor::position`].
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if a GPS sensor is not currently connected to the Smart Port.
+    pub fn location(&self) -> Result<Point2<Length>, PortError> {
+        let position = self.position()?;
+
+        Ok(Point2 {
+            x: Length::from_meters(position.x),
+            y: Length::from_meters(position.y),
+        })
+    }
+
+    /// Returns the RMS (Root Mean Squared) error for the sensor's [location reading].
+    ///
+    /// Since this is a [`Length`], it can be checked against a tolerance in any unit, such as
+    /// `gps.location_error()? > Length::from_inches(1.0)`.
+    ///
+    /// [location reading]: GpsSensor::location
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if a GPS sensor is not currently connected to the Smart Port.
+    pub fn location_error(&self) -> Result<Length, PortError> {
+        Ok(Length::from_meters(self.error()?))
+    }
+
     /// Returns the internal status code of the sensor.
     ///
     /// # Errors
@@ -851,6 +883,21 @@ impl SmartDevice for GpsSensor {
     }
 }
 
+impl Telemetry for GpsSensor {
+    const CHANNELS: &'static [&'static str] = &["x_m", "y_m", "heading_deg", "error_m"];
+
+    fn read_telemetry(&self, values: &mut [f64]) -> bool {
+        let (Ok(position), Ok(heading), Ok(error)) =
+            (self.position(), self.heading(), self.error())
+        else {
+            return false;
+        };
+
+        values.copy_from_slice(&[position.x, position.y, heading, error]);
+        true
+    }
+}
+
 impl From<GpsSensor> for SmartPort {
     fn from(device: GpsSensor) -> Self {
         device.port
@@ -69,9 +69,10 @@ use vex_sdk::{
 };
 use vexide_core::{float::Float, time::Instant};
 
-use super::{SmartDevice, SmartDeviceType, SmartPort};
+use super::{telemetry::Telemetry, SmartDevice, SmartDeviceType, SmartPort};
 use crate::{
     math::{EulerAngles, Quaternion, Vector3},
+    position::AngularVelocity,
     PortError,
 };
 
@@ -573,6 +574,26 @@ impl InertialSensor {
         })
     }
 
+    /// Returns the sensor's angular velocity about each of its axes.
+    ///
+    /// The degrees-per-second rates from [`InertialSensor::gyro_rate`] are wrapped in an
+    /// [`AngularVelocity`], so they can be read in radians per second for use with the
+    /// [`math`](crate::math) types or in RPM for comparing against a motor's velocity.
+    ///
+    /// # Errors
+    ///
+    /// - An [`InertialError::Port`] error is returned if there is not an inertial sensor connected to the port.
+    /// - An [`InertialError::StillCalibrating`] error is returned if the sensor is currently calibrating and cannot yet be used.
+    pub fn angular_velocity(&self) -> Result<Vector3<AngularVelocity>, InertialError> {
+        let rates = self.gyro_rate()?;
+
+        Ok(Vector3 {
+            x: AngularVelocity::from_degrees_per_second(rates.x),
+            y: AngularVelocity::from_degrees_per_second(rates.y),
+            z: AngularVelocity::from_degrees_per_second(rates.z),
+        })
+    }
+
     /// Returns the sensor's raw acceleration readings in g (multiples of ~9.8 m/s/s).
     ///
     /// # Errors
@@ -815,6 +836,29 @@ impl SmartDevice for InertialSensor {
         SmartDeviceType::Imu
     }
 }
+
+impl Telemetry for InertialSensor {
+    const CHANNELS: &'static [&'static str] = &[
+        "rotation_deg",
+        "gyro_x_dps",
+        "gyro_y_dps",
+        "gyro_z_dps",
+        "accel_x_g",
+        "accel_y_g",
+        "accel_z_g",
+    ];
+
+    fn read_telemetry(&self, values: &mut [f64]) -> bool {
+        let (Ok(rotation), Ok(gyro), Ok(accel)) =
+            (self.rotation(), self.gyro_rate(), self.acceleration())
+        else {
+            return false;
+        };
+
+        values.copy_from_slice(&[rotation, gyro.x, gyro.y, gyro.z, accel.x, accel.y, accel.z]);
+        true
+    }
+}
 impl From<InertialSensor> for SmartPort {
     fn from(device: InertialSensor) -> Self {
         device.port
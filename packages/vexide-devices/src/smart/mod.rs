@@ -51,6 +51,7 @@ pub mod motor;
 pub mod optical;
 pub mod rotation;
 pub mod serial;
+pub mod telemetry;
 pub mod vision;
 
 use core::time::Duration;
@@ -0,0 +1,329 @@
+//! Groups of motors.
+//!
+//! Mechanisms such as drivetrains and lifts are often powered by several motors that should
+//! always be given the same command. A [`MotorGroup`] wraps these motors so that they can be
+//! controlled and monitored as if they were a single motor.
+
+use alloc::vec::Vec;
+
+use snafu::Snafu;
+
+use super::{BrakeMode, Motor, MotorControl, MotorError, MotorFaults};
+use crate::{position::Position, smart::SmartDevice};
+
+/// Several Smart Motors that are controlled as one.
+///
+/// Commands sent to the group (such as [`MotorGroup::set_voltage`]) are sent to every motor in
+/// the group. Each motor applies the command according to its own [`Direction`](super::Direction),
+/// so motors mounted facing opposite ways should be created with opposite directions.
+///
+/// If a command can't be sent to some of the motors (for example, because one of them has been
+/// unplugged), it is still sent to the rest of the group, and the errors from the motors that
+/// failed are returned together in a [`MotorGroupError`].
+///
+/// # Examples
+///
+/// ```
+/// use vexide::prelude::*;
+///
+/// #[vexide::main]
+/// async fn main(peripherals: Peripherals) {
+///     let mut left_drive = MotorGroup::new([
+///         Motor::new(peripherals.port_1, Gearset::Blue, Direction::Forward),
+///         Motor::new(peripherals.port_2, Gearset::Blue, Direction::Reverse),
+///         Motor::new(peripherals.port_3, Gearset::Blue, Direction::Forward),
+///     ]);
+///
+///     if let Err(error) = left_drive.set_voltage(12.0) {
+///         for (port, error) in error.errors {
+///             println!("Motor on port {port} failed: {error}");
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct MotorGroup {
+    motors: Vec<Motor>,
+}
+
+impl MotorGroup {
+    /// Creates a new motor group from a collection of motors.
+    #[must_use]
+    pub fn new(motors: impl IntoIterator<Item = Motor>) -> Self {
+        Self {
+            motors: motors.into_iter().collect(),
+        }
+    }
+
+    /// Returns the motors in the group.
+    #[must_use]
+    pub fn motors(&self) -> &[Motor] {
+        self.motors.as_slice()
+    }
+
+    /// Returns mutable references to the motors in the group.
+    pub fn motors_mut(&mut self) -> &mut [Motor] {
+        self.motors.as_mut_slice()
+    }
+
+    /// Consumes the group, returning the motors that were in it.
+    #[must_use]
+    pub fn into_motors(self) -> Vec<Motor> {
+        self.motors
+    }
+
+    /// Returns the number of motors in the group.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.motors.len()
+    }
+
+    /// Returns `true` if the group contains no motors.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.motors.is_empty()
+    }
+
+    /// Runs a fallible operation on every motor, collecting the errors from motors that fail.
+    fn for_each_motor(
+        &mut self,
+        mut operation: impl FnMut(&mut Motor) -> Result<(), MotorError>,
+    ) -> Result<(), MotorGroupError> {
+        let errors: Vec<_> = self
+            .motors
+            .iter_mut()
+            .filter_map(|motor| {
+                let result = operation(motor);
+                result.err().map(|error| (motor.port_number(), error))
+            })
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(MotorGroupError { errors })
+        }
+    }
+
+    /// Reads a value from every motor that can be read from.
+    ///
+    /// Fails only if no motors could be read from.
+    fn read_motors<T>(
+        &self,
+        read: impl Fn(&Motor) -> Result<T, MotorError>,
+    ) -> Result<Vec<T>, MotorGroupError> {
+        let mut values = Vec::with_capacity(self.motors.len());
+        let mut errors = Vec::new();
+
+        for motor in &self.motors {
+            match read(motor) {
+                Ok(value) => values.push(value),
+                Err(error) => errors.push((motor.port_number(), error)),
+            }
+        }
+
+        if values.is_empty() {
+            Err(MotorGroupError { errors })
+        } else {
+            Ok(values)
+        }
+    }
+
+    /// Sets the target that every motor in the group should attempt to reach.
+    ///
+    /// See [`Motor::set_target`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// - A [`MotorGroupError`] is returned if any of the motors failed to receive the target.
+    ///   The target is still sent to every other motor in the group.
+    pub fn set_target(&mut self, target: MotorControl) -> Result<(), MotorGroupError> {
+        self.for_each_motor(|motor| motor.set_target(target))
+    }
+
+    /// Sets every motor in the group's target to a given [`BrakeMode`].
+    ///
+    /// # Errors
+    ///
+    /// - A [`MotorGroupError`] is returned if any of the motors failed to receive the target.
+    ///   The target is still sent to every other motor in the group.
+    pub fn brake(&mut self, mode: BrakeMode) -> Result<(), MotorGroupError> {
+        self.set_target(MotorControl::Brake(mode))
+    }
+
+    /// Spins every motor in the group at a target velocity.
+    ///
+    /// See [`Motor::set_velocity`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// - A [`MotorGroupError`] is returned if any of the motors failed to receive the target.
+    ///   The target is still sent to every other motor in the group.
+    pub fn set_velocity(&mut self, rpm: i32) -> Result<(), MotorGroupError> {
+        self.set_target(MotorControl::Velocity(rpm))
+    }
+
+    /// Sets the output voltage of every motor in the group.
+    ///
+    /// See [`Motor::set_voltage`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// - A [`MotorGroupError`] is returned if any of the motors failed to receive the target.
+    ///   The target is still sent to every other motor in the group.
+    pub fn set_voltage(&mut self, volts: f64) -> Result<(), MotorGroupError> {
+        self.set_target(MotorControl::Voltage(volts))
+    }
+
+    /// Sets an absolute position target for every motor in the group to attempt to reach.
+    ///
+    /// See [`Motor::set_position_target`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// - A [`MotorGroupError`] is returned if any of the motors failed to receive the target.
+    ///   The target is still sent to every other motor in the group.
+    pub fn set_position_target(
+        &mut self,
+        position: Position,
+        velocity: i32,
+    ) -> Result<(), MotorGroupError> {
+        self.set_target(MotorControl::Position(position, velocity))
+    }
+
+    /// Returns the mean velocity of the motors in the group in RPM.
+    ///
+    /// Motors that can't be read from (for example, because they are disconnected) are left
+    /// out of the mean.
+    ///
+    /// # Errors
+    ///
+    /// - A [`MotorGroupError`] is returned if none of the motors could be read from.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn velocity(&self) -> Result<f64, MotorGroupError> {
+        let velocities = self.read_motors(Motor::velocity)?;
+        Ok(velocities.iter().sum::<f64>() / velocities.len() as f64)
+    }
+
+    /// Returns the largest voltage that can be applied to every motor in the group.
+    ///
+    /// This is the lowest [maximum voltage](Motor::max_voltage) of the motors in the group, or
+    /// [`Motor::V5_MAX_VOLTAGE`] if the group is empty.
+    #[must_use]
+    pub fn max_voltage(&self) -> f64 {
+        self.motors
+            .iter()
+            .map(Motor::max_voltage)
+            .reduce(f64::min)
+            .unwrap_or(Motor::V5_MAX_VOLTAGE)
+    }
+
+    /// Returns the rated maximum velocity of the slowest motor in the group in RPM.
+    ///
+    /// Each motor's maximum is taken from its [gearset](Motor::gearset) and scaled by its
+    /// [gear ratio](Motor::gear_ratio). Motors that can't be read from (for example, because
+    /// they are disconnected) are ignored.
+    ///
+    /// # Errors
+    ///
+    /// - A [`MotorGroupError`] is returned if none of the motors could be read from.
+    pub fn max_velocity(&self) -> Result<f64, MotorGroupError> {
+        Ok(self
+            .read_motors(|motor| Ok(motor.gearset()?.max_rpm() / motor.gear_ratio()))?
+            .into_iter()
+            .fold(f64::INFINITY, f64::min))
+    }
+
+    /// Returns the mean position of the motors in the group.
+    ///
+    /// Motors that can't be read from (for example, because they are disconnected) are left
+    /// out of the mean.
+    ///
+    /// # Errors
+    ///
+    /// - A [`MotorGroupError`] is returned if none of the motors could be read from.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn position(&self) -> Result<Position, MotorGroupError> {
+        let positions = self.read_motors(Motor::position)?;
+        let revolutions: f64 = positions.iter().map(Position::as_revolutions).sum();
+        Ok(Position::from_revolutions(
+            revolutions / positions.len() as f64,
+        ))
+    }
+
+    /// Returns the total electrical current drawn by the motors in the group in amps.
+    ///
+    /// Motors that can't be read from (for example, because they are disconnected) are left
+    /// out of the total.
+    ///
+    /// # Errors
+    ///
+    /// - A [`MotorGroupError`] is returned if none of the motors could be read from.
+    pub fn current(&self) -> Result<f64, MotorGroupError> {
+        Ok(self.read_motors(Motor::current)?.into_iter().sum())
+    }
+
+    /// Returns the total power drawn by the motors in the group in Watts.
+    ///
+    /// Motors that can't be read from (for example, because they are disconnected) are left
+    /// out of the total.
+    ///
+    /// # Errors
+    ///
+    /// - A [`MotorGroupError`] is returned if none of the motors could be read from.
+    pub fn power(&self) -> Result<f64, MotorGroupError> {
+        Ok(self.read_motors(Motor::power)?.into_iter().sum())
+    }
+
+    /// Returns the temperature of the hottest motor in the group in degrees Celsius.
+    ///
+    /// Motors that can't be read from (for example, because they are disconnected) are
+    /// ignored.
+    ///
+    /// # Errors
+    ///
+    /// - A [`MotorGroupError`] is returned if none of the motors could be read from.
+    pub fn temperature(&self) -> Result<f64, MotorGroupError> {
+        Ok(self
+            .read_motors(Motor::temperature)?
+            .into_iter()
+            .fold(f64::NEG_INFINITY, f64::max))
+    }
+
+    /// Returns the fault flags of every motor in the group combined.
+    ///
+    /// Motors that can't be read from (for example, because they are disconnected) are
+    /// ignored.
+    ///
+    /// # Errors
+    ///
+    /// - A [`MotorGroupError`] is returned if none of the motors could be read from.
+    pub fn faults(&self) -> Result<MotorFaults, MotorGroupError> {
+        Ok(self
+            .read_motors(Motor::faults)?
+            .into_iter()
+            .fold(MotorFaults::empty(), |faults, motor_faults| {
+                faults | motor_faults
+            }))
+    }
+}
+
+impl From<Vec<Motor>> for MotorGroup {
+    fn from(motors: Vec<Motor>) -> Self {
+        Self { motors }
+    }
+}
+
+impl FromIterator<Motor> for MotorGroup {
+    fn from_iter<I: IntoIterator<Item = Motor>>(iter: I) -> Self {
+        Self::new(iter)
+    }
+}
+
+/// Errors from the motors in a [`MotorGroup`] that failed to complete an operation.
+#[derive(Debug, Snafu)]
+#[snafu(display("{} motor(s) in the group failed", errors.len()))]
+pub struct MotorGroupError {
+    /// The port number and error of each motor that failed.
+    pub errors: Vec<(u8, MotorError)>,
+}
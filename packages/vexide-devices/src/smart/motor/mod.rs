@@ -49,6 +49,28 @@
 //!
 //! The [`Motor`] API can make use of these builtin control features through the [`MotorControl`] type,
 //! which describes an action that the motor should perform.
+//!
+//! # Motor Groups
+//!
+//! Mechanisms driven by several motors at once can use a [`MotorGroup`] to send the same command
+//! to every motor and read their combined telemetry.
+//!
+//! # Stall Protection
+//!
+//! Motors that are blocked while being driven draw high current and quickly overheat. A
+//! [`StallMonitor`] can watch a motor for stalls and react to them by lowering its current limit,
+//! briefly reversing it, or stopping it.
+//!
+//! # Thermal Management
+//!
+//! VEXos sharply limits a motor's output once it reaches its internal temperature limit. A
+//! [`ThermalManager`] instead lowers a motor's voltage and current limits gradually as it heats up,
+//! and can warn drivers on the controller before the motor loses power.
+//!
+//! # Health Monitoring
+//!
+//! [`Motor::updates`] returns an async stream that yields whenever a motor's fault or status flags
+//! change, or when it is disconnected or reconnected, and counts the faults it has tripped.
 
 use core::time::Duration;
 
@@ -68,9 +90,25 @@ use vex_sdk::{
 };
 #[cfg(feature = "dangerous_motor_tuning")]
 use vex_sdk::{vexDeviceMotorPositionPidSet, vexDeviceMotorVelocityPidSet, V5_DeviceMotorPid};
+use vexide_core::float::Float;
 
-use super::{SmartDevice, SmartDeviceTimestamp, SmartDeviceType, SmartPort};
-use crate::{position::Position, PortError};
+use super::{telemetry::Telemetry, SmartDevice, SmartDeviceTimestamp, SmartDeviceType, SmartPort};
+use crate::{
+    position::{AngularVelocity, Position},
+    PortError,
+};
+
+mod group;
+mod settle;
+mod stall;
+mod thermal;
+mod updates;
+
+pub use group::{MotorGroup, MotorGroupError};
+pub use settle::{MotorSettleFuture, MotorSettleOptions};
+pub use stall::{MotorStallOptions, StallEvent, StallMonitor, StallPolicy};
+pub use thermal::{ThermalBand, ThermalManager, ThermalReport};
+pub use updates::{MotorFaultCounts, MotorHealth, MotorUpdates};
 
 /// A motor plugged into a Smart Port.
 #[derive(Debug, PartialEq)]
@@ -80,6 +118,7 @@ pub struct Motor {
     device: V5_DeviceT,
 
     motor_type: MotorType,
+    gear_ratio: f64,
 }
 
 // SAFETY: Required because we store a raw pointer to the device handle to avoid it getting from the
@@ -226,6 +265,7 @@ impl Motor {
             target: MotorControl::Voltage(0.0),
             device,
             motor_type,
+            gear_ratio: 1.0,
         }
     }
 
@@ -304,7 +344,7 @@ impl Motor {
                     self.device,
                     vex_sdk::V5MotorBrakeMode::kV5MotorBrakeModeCoast,
                 );
-                vexDeviceMotorVelocitySet(self.device, rpm);
+                vexDeviceMotorVelocitySet(self.device, self.to_motor_velocity(rpm));
             },
             MotorControl::Voltage(volts) => unsafe {
                 vexDeviceMotorBrakeModeSet(
@@ -322,8 +362,9 @@ impl Motor {
                 #[allow(clippy::cast_precision_loss)]
                 vexDeviceMotorAbsoluteTargetSet(
                     self.device,
-                    position.as_ticks(gearset.ticks_per_revolution()) as f64,
-                    velocity,
+                    self.to_motor_position(position)
+                        .as_ticks(gearset.ticks_per_revolution()) as f64,
+                    self.to_motor_velocity(velocity),
                 );
             },
         }
@@ -354,6 +395,8 @@ impl Motor {
 
     /// Spins the motor at a target velocity.
     ///
+    /// If an external gear ratio has been set with [`Motor::set_gear_ratio`], this is a velocity of the output shaft.
+    ///
     /// This velocity corresponds to different actual speeds in RPM depending on the gearset used for the motor.
     /// Velocity is held with an internal PID controller to ensure consistent speed, as opposed to setting the
     /// motor's voltage.
@@ -427,6 +470,8 @@ impl Motor {
 
     /// Sets an absolute position target for the motor to attempt to reach.
     ///
+    /// If an external gear ratio has been set with [`Motor::set_gear_ratio`], this is a position and velocity of the output shaft.
+    ///
     /// # Errors
     ///
     /// - A [`MotorError::Port`] error is returned if a motor device is not currently connected to the Smart Port.
@@ -476,7 +521,7 @@ impl Motor {
         self.validate_port()?;
 
         unsafe {
-            vexDeviceMotorVelocityUpdate(self.device, velocity);
+            vexDeviceMotorVelocityUpdate(self.device, self.to_motor_velocity(velocity));
         }
 
         if let MotorControl::Position(position, _) = self.target {
@@ -645,8 +690,73 @@ impl Motor {
         self.motor_type.max_voltage()
     }
 
+    /// Sets the external gear ratio between the motor and the shaft it drives.
+    ///
+    /// The ratio is the number of times the motor turns for each turn of the output shaft, so a
+    /// 12-tooth gear on the motor driving a 36-tooth gear has a ratio of 3.0. Once set, the
+    /// motor's position and velocity methods (such as [`Motor::position`],
+    /// [`Motor::velocity`], [`Motor::set_position_target`], and [`Motor::set_velocity`]) are all
+    /// measured at the output shaft rather than the motor. Targets given in RPM are rounded to the
+    /// nearest whole RPM at the motor.
+    ///
+    /// This is separate from the motor's internal [`Gearset`], which is always accounted for.
+    /// By default, the ratio is 1.0.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `ratio` is not a finite number greater than zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vexide::prelude::*;
+    ///
+    /// #[vexide::main]
+    /// async fn main(peripherals: Peripherals) {
+    ///     let mut motor = Motor::new(peripherals.port_1, Gearset::Blue, Direction::Forward);
+    ///
+    ///     // The motor drives a 36-tooth gear with a 12-tooth gear.
+    ///     motor.set_gear_ratio(36.0 / 12.0);
+    ///
+    ///     // Turn the output shaft (and the motor three times as far).
+    ///     _ = motor.set_position_target(Position::from_degrees(90.0), 200);
+    /// }
+    /// ```
+    pub fn set_gear_ratio(&mut self, ratio: f64) {
+        assert!(
+            ratio.is_finite() && ratio > 0.0,
+            "{ratio} is not a valid gear ratio. Gear ratios must be finite and greater than zero."
+        );
+        self.gear_ratio = ratio;
+    }
+
+    /// Returns the external gear ratio between the motor and the shaft it drives.
+    ///
+    /// See [`Motor::set_gear_ratio`] for more information.
+    #[must_use]
+    pub const fn gear_ratio(&self) -> f64 {
+        self.gear_ratio
+    }
+
+    /// Converts a velocity at the output shaft to a velocity at the motor.
+    fn to_motor_velocity(&self, rpm: i32) -> i32 {
+        (f64::from(rpm) * self.gear_ratio).round() as i32
+    }
+
+    /// Converts a position at the output shaft to a position at the motor.
+    fn to_motor_position(&self, position: Position) -> Position {
+        Position::from_revolutions(position.as_revolutions() * self.gear_ratio)
+    }
+
+    /// Converts a position at the motor to a position at the output shaft.
+    fn to_output_position(&self, position: Position) -> Position {
+        Position::from_revolutions(position.as_revolutions() / self.gear_ratio)
+    }
+
     /// Returns the motor's estimate of its angular velocity in rotations per minute (RPM).
     ///
+    /// If an external gear ratio has been set with [`Motor::set_gear_ratio`], this is the velocity of the output shaft.
+    ///
     /// # Accuracy
     ///
     /// In some cases, this reported value may be noisy or inaccurate, especially for systems where accurate
@@ -704,7 +814,19 @@ impl Motor {
     /// ```
     pub fn velocity(&self) -> Result<f64, MotorError> {
         self.validate_port()?;
-        Ok(unsafe { vexDeviceMotorActualVelocityGet(self.device) })
+        Ok(unsafe { vexDeviceMotorActualVelocityGet(self.device) } / self.gear_ratio)
+    }
+
+    /// Returns the motor's estimate of its angular velocity.
+    ///
+    /// If an external gear ratio has been set with [`Motor::set_gear_ratio`], this is the velocity of the output shaft.
+    /// See [`Motor::velocity`] for notes on the accuracy of the estimate, which is reported there in RPM.
+    ///
+    /// # Errors
+    ///
+    /// - A [`MotorError::Port`] error is returned if a motor device is not currently connected to the Smart Port.
+    pub fn angular_velocity(&self) -> Result<AngularVelocity, MotorError> {
+        Ok(AngularVelocity::from_rpm(self.velocity()?))
     }
 
     /// Returns the power drawn by the motor in Watts.
@@ -788,6 +910,8 @@ impl Motor {
 
     /// Returns the current position of the motor.
     ///
+    /// If an external gear ratio has been set with [`Motor::set_gear_ratio`], this is the position of the output shaft.
+    ///
     /// # Errors
     ///
     /// - A [`MotorError::Port`] error is returned if a motor device is not currently connected to the Smart Port.
@@ -810,10 +934,10 @@ impl Motor {
     /// ```
     pub fn position(&self) -> Result<Position, MotorError> {
         let gearset = self.gearset()?;
-        Ok(Position::from_ticks(
+        Ok(self.to_output_position(Position::from_ticks(
             unsafe { vexDeviceMotorPositionGet(self.device) } as i64,
             gearset.ticks_per_revolution(),
-        ))
+        )))
     }
 
     /// Returns the most recently recorded raw encoder tick data from the motor's IME
@@ -939,6 +1063,8 @@ impl Motor {
 
     /// Sets the current encoder position to the given position without moving the motor.
     ///
+    /// If an external gear ratio has been set with [`Motor::set_gear_ratio`], this is a position of the output shaft.
+    ///
     /// Analogous to taring or resetting the encoder so that the new position is equal to the given position.
     ///
     /// # Errors
@@ -976,7 +1102,8 @@ impl Motor {
             vexDeviceMotorPositionSet(
                 self.device,
                 // NOTE: No precision loss since ticks are not fractional.
-                position.as_ticks(gearset.ticks_per_revolution()) as f64,
+                self.to_motor_position(position)
+                    .as_ticks(gearset.ticks_per_revolution()) as f64,
             );
         }
 
@@ -1470,6 +1597,31 @@ impl SmartDevice for Motor {
         SmartDeviceType::Motor
     }
 }
+
+impl Telemetry for Motor {
+    const CHANNELS: &'static [&'static str] = &[
+        "velocity_rpm",
+        "voltage_v",
+        "current_a",
+        "torque_nm",
+        "position_deg",
+    ];
+
+    fn read_telemetry(&self, values: &mut [f64]) -> bool {
+        let (Ok(velocity), Ok(voltage), Ok(current), Ok(torque), Ok(position)) = (
+            self.velocity(),
+            self.voltage(),
+            self.current(),
+            self.torque(),
+            self.position(),
+        ) else {
+            return false;
+        };
+
+        values.copy_from_slice(&[velocity, voltage, current, torque, position.as_degrees()]);
+        true
+    }
+}
 impl From<Motor> for SmartPort {
     fn from(device: Motor) -> Self {
         device.port
@@ -1694,4 +1846,13 @@ pub enum MotorError {
 
     /// EXP motors do not have customizable gearsets.
     SetGearsetExp,
+
+    /// The motor does not have a position target to settle at.
+    NotPositionTarget,
+
+    /// The motor stalled before reaching its position target.
+    Stalled,
+
+    /// The motor took too long to settle at its position target.
+    SettleTimedOut,
 }
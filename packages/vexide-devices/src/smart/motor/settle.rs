@@ -0,0 +1,172 @@
+//! Waiting for motors to reach their position targets.
+
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use snafu::ensure;
+use vexide_core::time::Instant;
+
+use super::{
+    Motor, MotorControl, MotorError, NotPositionTargetSnafu, SettleTimedOutSnafu, StalledSnafu,
+};
+use crate::position::Position;
+
+/// The fraction of a motor's current limit above which it is considered to be stalling if it
+/// isn't moving.
+const STALL_CURRENT_RATIO: f64 = 0.9;
+
+/// Conditions for a [`Motor`] to be considered settled at its position target.
+///
+/// Used by [`Motor::wait_until_settled`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MotorSettleOptions {
+    /// How far the motor may be from its position target while settled.
+    pub position_tolerance: Position,
+
+    /// The fastest the motor may be spinning (in RPM) while settled.
+    pub velocity_tolerance: f64,
+
+    /// How long the motor must stay within both tolerances before it is settled.
+    pub dwell: Duration,
+
+    /// How long the motor may be stalled (not moving while drawing close to its current
+    /// limit) before giving up.
+    pub stall_timeout: Duration,
+
+    /// How long to wait for the motor to settle before giving up.
+    pub timeout: Duration,
+}
+
+impl Default for MotorSettleOptions {
+    fn default() -> Self {
+        Self {
+            position_tolerance: Position::from_degrees(5.0),
+            velocity_tolerance: 5.0,
+            dwell: Duration::from_millis(100),
+            stall_timeout: Duration::from_millis(250),
+            timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+impl Motor {
+    /// Waits for the motor to settle at its position target.
+    ///
+    /// Returns a [`MotorSettleFuture`] that resolves once the motor has been within
+    /// `options.position_tolerance` of the target set with [`Motor::set_position_target`]
+    /// and slower than `options.velocity_tolerance` for at least `options.dwell`.
+    ///
+    /// # Errors
+    ///
+    /// - A [`MotorError::NotPositionTarget`] error is returned if the motor's target is not a
+    ///   [`MotorControl::Position`] target.
+    /// - A [`MotorError::Stalled`] error is returned if the motor stops moving before reaching
+    ///   its target while drawing close to its current limit for longer than
+    ///   `options.stall_timeout`.
+    /// - A [`MotorError::SettleTimedOut`] error is returned if the motor hasn't settled within
+    ///   `options.timeout`.
+    /// - A [`MotorError::Port`] error is returned if a motor device is not currently connected
+    ///   to the Smart Port.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vexide::{devices::smart::motor::MotorSettleOptions, prelude::*};
+    ///
+    /// #[vexide::main]
+    /// async fn main(peripherals: Peripherals) {
+    ///     let mut motor = Motor::new(peripherals.port_1, Gearset::Green, Direction::Forward);
+    ///
+    ///     motor
+    ///         .set_position_target(Position::from_degrees(90.0), 200)
+    ///         .unwrap();
+    ///
+    ///     match motor.wait_until_settled(MotorSettleOptions::default()).await {
+    ///         Ok(()) => println!("Motor reached its target"),
+    ///         Err(error) => println!("Motor failed to reach its target: {error}"),
+    ///     }
+    /// }
+    /// ```
+    pub fn wait_until_settled(&self, options: MotorSettleOptions) -> MotorSettleFuture<'_> {
+        MotorSettleFuture {
+            motor: self,
+            options,
+            start: Instant::now(),
+            settled_since: None,
+            stalled_since: None,
+        }
+    }
+}
+
+/// Future that waits for a motor to reach its position target
+/// created with [`Motor::wait_until_settled`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+#[derive(Debug)]
+pub struct MotorSettleFuture<'a> {
+    motor: &'a Motor,
+    options: MotorSettleOptions,
+    start: Instant,
+    settled_since: Option<Instant>,
+    stalled_since: Option<Instant>,
+}
+
+impl MotorSettleFuture<'_> {
+    /// Returns `true` if the motor has settled, or an error if it never will.
+    fn is_settled(&mut self) -> Result<bool, MotorError> {
+        let MotorControl::Position(target, _) = self.motor.target() else {
+            return NotPositionTargetSnafu.fail();
+        };
+
+        let error = (target - self.motor.position()?).as_degrees().abs();
+        let stopped = self.motor.velocity()?.abs() <= self.options.velocity_tolerance;
+
+        if stopped && error <= self.options.position_tolerance.as_degrees().abs() {
+            self.stalled_since = None;
+
+            let settled_since = *self.settled_since.get_or_insert_with(Instant::now);
+            return Ok(settled_since.elapsed() >= self.options.dwell);
+        }
+        self.settled_since = None;
+
+        // A motor that isn't moving towards its target while pushing as hard as it's allowed
+        // to is most likely blocked by something.
+        if stopped
+            && (self.motor.is_over_current()?
+                || self.motor.current()? >= self.motor.current_limit()? * STALL_CURRENT_RATIO)
+        {
+            let stalled_since = *self.stalled_since.get_or_insert_with(Instant::now);
+            ensure!(
+                stalled_since.elapsed() < self.options.stall_timeout,
+                StalledSnafu
+            );
+        } else {
+            self.stalled_since = None;
+        }
+
+        ensure!(
+            self.start.elapsed() < self.options.timeout,
+            SettleTimedOutSnafu
+        );
+
+        Ok(false)
+    }
+}
+
+impl Future for MotorSettleFuture<'_> {
+    type Output = Result<(), MotorError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.get_mut().is_settled() {
+            Ok(true) => Poll::Ready(Ok(())),
+            Ok(false) => {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+            Err(error) => Poll::Ready(Err(error)),
+        }
+    }
+}
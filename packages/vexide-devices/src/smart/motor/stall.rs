@@ -0,0 +1,244 @@
+//! Stall detection and protection.
+
+use core::time::Duration;
+
+use vexide_core::time::Instant;
+
+use super::{BrakeMode, Motor, MotorControl, MotorError};
+
+/// Conditions for a [`Motor`] to be considered stalled.
+///
+/// Used by [`StallMonitor`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MotorStallOptions {
+    /// The current (in amps) at or above which the motor may be stalling.
+    pub current_threshold: f64,
+
+    /// The speed (in RPM) at or below which the motor may be stalling.
+    pub velocity_threshold: f64,
+
+    /// How long the motor must be drawing high current without moving before it is considered
+    /// stalled.
+    pub duration: Duration,
+}
+
+impl Default for MotorStallOptions {
+    fn default() -> Self {
+        Self {
+            current_threshold: 2.0,
+            velocity_threshold: 5.0,
+            duration: Duration::from_millis(250),
+        }
+    }
+}
+
+/// What a [`StallMonitor`] does to a motor once it has stalled.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StallPolicy {
+    /// Lowers the motor's current limit to the given value (in amps).
+    ///
+    /// The limit is left in place once the stall clears.
+    LimitCurrent(f64),
+
+    /// Spins the motor in the opposite direction at the given voltage for a duration, then
+    /// restores its previous target.
+    ///
+    /// If the motor is given a new target while it is reversing (for example, a driver stops
+    /// the intake), the previous target is not restored and the motor is left alone.
+    ///
+    /// This is useful for clearing jams in mechanisms such as intakes.
+    Reverse {
+        /// The voltage to spin in reverse at. The sign is ignored.
+        volts: f64,
+
+        /// How long to spin in reverse for.
+        duration: Duration,
+    },
+
+    /// Stops the motor with the given [`BrakeMode`].
+    Stop(BrakeMode),
+
+    /// Leaves the motor alone, only reporting the stall.
+    Report,
+}
+
+/// A stall detected by a [`StallMonitor`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StallEvent {
+    /// The current drawn by the motor (in amps) when the stall was detected.
+    pub current: f64,
+
+    /// The velocity of the motor (in RPM) when the stall was detected.
+    pub velocity: f64,
+
+    /// The target the motor had before the stall policy was applied.
+    pub target: MotorControl,
+
+    /// The policy that was applied to the motor.
+    pub policy: StallPolicy,
+}
+
+/// Watches a [`Motor`] for stalls and protects it when one occurs.
+///
+/// A motor is considered stalled once it has drawn at least
+/// [`current_threshold`](MotorStallOptions::current_threshold) amps while spinning no faster
+/// than [`velocity_threshold`](MotorStallOptions::velocity_threshold) RPM for longer than
+/// [`duration`](MotorStallOptions::duration). Once that happens, the monitor applies its
+/// [`StallPolicy`] to the motor.
+///
+/// The monitor does nothing on its own, and must be updated regularly with
+/// [`StallMonitor::update`], either from a control loop or from a background task.
+///
+/// # Examples
+///
+/// Reversing an intake when it jams:
+///
+/// ```
+/// use core::time::Duration;
+///
+/// use vexide::{
+///     devices::smart::motor::{MotorStallOptions, StallMonitor, StallPolicy},
+///     prelude::*,
+/// };
+///
+/// #[vexide::main]
+/// async fn main(peripherals: Peripherals) {
+///     let mut intake = Motor::new(peripherals.port_1, Gearset::Blue, Direction::Forward);
+///     let mut monitor = StallMonitor::new(
+///         MotorStallOptions::default(),
+///         StallPolicy::Reverse {
+///             volts: 6.0,
+///             duration: Duration::from_millis(300),
+///         },
+///     );
+///
+///     intake.set_voltage(12.0).unwrap();
+///
+///     loop {
+///         if let Ok(Some(event)) = monitor.update(&mut intake) {
+///             println!("Intake jammed while drawing {:.2}A", event.current);
+///         }
+///
+///         sleep(Motor::UPDATE_INTERVAL).await;
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct StallMonitor {
+    options: MotorStallOptions,
+    policy: StallPolicy,
+    stalled_since: Option<Instant>,
+    /// When the current reverse started, the target to restore, and the reverse target.
+    reversing: Option<(Instant, MotorControl, MotorControl)>,
+}
+
+impl StallMonitor {
+    /// Creates a new stall monitor that applies `policy` to stalled motors.
+    #[must_use]
+    pub const fn new(options: MotorStallOptions, policy: StallPolicy) -> Self {
+        Self {
+            options,
+            policy,
+            stalled_since: None,
+            reversing: None,
+        }
+    }
+
+    /// Returns the conditions for a motor to be considered stalled.
+    #[must_use]
+    pub const fn options(&self) -> MotorStallOptions {
+        self.options
+    }
+
+    /// Returns the policy applied to stalled motors.
+    #[must_use]
+    pub const fn policy(&self) -> StallPolicy {
+        self.policy
+    }
+
+    /// Returns `true` if the monitor is currently reversing a motor to clear a stall.
+    #[must_use]
+    pub const fn is_reversing(&self) -> bool {
+        self.reversing.is_some()
+    }
+
+    /// Clears any stall in progress without restoring the motor's target.
+    pub const fn reset(&mut self) {
+        self.stalled_since = None;
+        self.reversing = None;
+    }
+
+    /// Checks the motor for a stall, applying the monitor's [`StallPolicy`] if one is found.
+    ///
+    /// This should be called regularly (ideally every [`Motor::UPDATE_INTERVAL`]) with the same
+    /// motor. Returns a [`StallEvent`] on the update where a stall is detected.
+    ///
+    /// While a [`StallPolicy::Reverse`] is in progress, the motor isn't checked for stalls.
+    /// The motor's previous target is restored by the first update after the reverse has
+    /// finished, unless the motor has been given a different target since the reverse
+    /// started.
+    ///
+    /// # Errors
+    ///
+    /// - A [`MotorError::Port`] error is returned if a motor device is not currently connected
+    ///   to the Smart Port.
+    pub fn update(&mut self, motor: &mut Motor) -> Result<Option<StallEvent>, MotorError> {
+        if let Some((started, target, reverse)) = self.reversing {
+            let StallPolicy::Reverse { duration, .. } = self.policy else {
+                unreachable!("only the reverse policy reverses motors");
+            };
+
+            if started.elapsed() < duration {
+                return Ok(None);
+            }
+
+            self.reversing = None;
+            // Don't override anything the motor was told to do while it was reversing.
+            if motor.target() == reverse {
+                motor.set_target(target)?;
+            }
+            return Ok(None);
+        }
+
+        let current = motor.current()?;
+        let velocity = motor.velocity()?;
+
+        if current < self.options.current_threshold
+            || velocity.abs() > self.options.velocity_threshold
+        {
+            self.stalled_since = None;
+            return Ok(None);
+        }
+
+        let stalled_since = *self.stalled_since.get_or_insert_with(Instant::now);
+        if stalled_since.elapsed() < self.options.duration {
+            return Ok(None);
+        }
+        self.stalled_since = None;
+
+        let target = motor.target();
+        match self.policy {
+            StallPolicy::LimitCurrent(limit) => motor.set_current_limit(limit)?,
+            StallPolicy::Reverse { volts, .. } => {
+                // Spin against the direction the motor was trying to go.
+                let volts = if motor.voltage()? > 0.0 {
+                    -volts.abs()
+                } else {
+                    volts.abs()
+                };
+
+                motor.set_voltage(volts)?;
+                self.reversing = Some((Instant::now(), target, motor.target()));
+            }
+            StallPolicy::Stop(mode) => motor.brake(mode)?,
+            StallPolicy::Report => {}
+        }
+
+        Ok(Some(StallEvent {
+            current,
+            velocity,
+            target,
+            policy: self.policy,
+        }))
+    }
+}
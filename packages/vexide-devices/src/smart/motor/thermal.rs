@@ -0,0 +1,314 @@
+//! Temperature-aware motor derating.
+
+use alloc::{format, vec::Vec};
+
+use super::{Motor, MotorError};
+use crate::{
+    controller::{Controller, ControllerError},
+    smart::SmartDevice,
+};
+
+/// A temperature above which a [`ThermalManager`] limits a motor's output.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThermalBand {
+    /// The temperature (in degrees Celsius) at which this band starts.
+    pub temperature: f64,
+
+    /// The fraction of the motor's full voltage and current limits it is allowed to use at
+    /// this band's temperature, from 0.0 to 1.0.
+    pub scale: f64,
+}
+
+/// The thermal state of a motor, as reported by [`ThermalManager::update`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThermalReport {
+    /// The port number of the motor.
+    pub port: u8,
+
+    /// The temperature of the motor in degrees Celsius.
+    pub temperature: f64,
+
+    /// How many degrees Celsius the motor can heat up by before VEXos stops it.
+    pub headroom: f64,
+
+    /// The fraction of its full voltage and current limits the motor is now allowed to use.
+    pub scale: f64,
+
+    /// The index of the band the motor is currently in, or `None` if it is cooler than every
+    /// band.
+    pub band: Option<usize>,
+
+    /// `true` if the motor has just reached a band hotter than any it had reached before.
+    ///
+    /// This is only set once per band, so a motor hovering around a band's temperature
+    /// doesn't warn repeatedly as it heats up and cools down.
+    pub warning: bool,
+}
+
+impl ThermalReport {
+    /// Shows this report on a controller's screen and rumbles the controller.
+    ///
+    /// The report is written to the given line of the screen as the motor's port number,
+    /// temperature, and the percentage of its full output it is limited to (for example,
+    /// `P3 HOT 55C 60%`).
+    ///
+    /// # Errors
+    ///
+    /// - A [`ControllerError::Offline`] error is returned if the controller is not connected.
+    /// - A [`ControllerError::WriteBusy`] error is returned if the controller's screen was
+    ///   written to too recently.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `line` is not a valid controller screen line.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vexide::{devices::smart::motor::ThermalManager, prelude::*};
+    ///
+    /// #[vexide::main]
+    /// async fn main(peripherals: Peripherals) {
+    ///     let mut controller = peripherals.primary_controller;
+    ///     let mut motor = Motor::new(peripherals.port_1, Gearset::Green, Direction::Forward);
+    ///     let mut thermals = ThermalManager::default();
+    ///
+    ///     if let Ok(report) = thermals.update(&mut motor) {
+    ///         _ = report.show_on(&mut controller, 1);
+    ///     }
+    /// }
+    /// ```
+    pub fn show_on(&self, controller: &mut Controller, line: u8) -> Result<(), ControllerError> {
+        controller.screen.try_set_text(
+            format!(
+                "P{} HOT {:.0}C {:.0}%",
+                self.port,
+                self.temperature,
+                self.scale * 100.0
+            ),
+            line,
+            1,
+        )?;
+        controller.try_rumble(".")
+    }
+}
+
+/// Gradually limits the output of motors as they heat up.
+///
+/// VEXos only limits a motor's output once it has reached its internal temperature limit,
+/// which causes a sudden loss of power. A thermal manager instead scales down each motor's
+/// voltage and current limits as its temperature rises through a set of [`ThermalBand`]s,
+/// interpolating between bands so that the motor loses power gradually.
+///
+/// The manager does nothing on its own, and must be updated regularly for every motor it
+/// manages with [`ThermalManager::update`].
+///
+/// The limits a motor has when the manager first sees it are taken as its full limits, and
+/// the manager only writes new limits when the motor's scale changes. If the limits are
+/// changed elsewhere in the meantime (for example, by a [`StallMonitor`](super::StallMonitor)),
+/// the new limits are taken as the motor's full limits from then on.
+///
+/// # Examples
+///
+/// ```
+/// use vexide::{devices::smart::motor::ThermalManager, prelude::*};
+///
+/// #[vexide::main]
+/// async fn main(peripherals: Peripherals) {
+///     let mut controller = peripherals.primary_controller;
+///     let mut motors = [
+///         Motor::new(peripherals.port_1, Gearset::Blue, Direction::Forward),
+///         Motor::new(peripherals.port_2, Gearset::Blue, Direction::Reverse),
+///     ];
+///     let mut thermals = ThermalManager::default();
+///
+///     loop {
+///         for motor in &mut motors {
+///             if let Ok(report) = thermals.update(motor) {
+///                 if report.warning {
+///                     _ = report.show_on(&mut controller, 3);
+///                 }
+///             }
+///         }
+///
+///         sleep(Motor::UPDATE_INTERVAL).await;
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThermalManager {
+    bands: Vec<ThermalBand>,
+    /// The state of the motor on each port, indexed by port number.
+    motors: [Option<ManagedMotor>; 21],
+}
+
+/// A motor that a [`ThermalManager`] has seen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ManagedMotor {
+    /// The motor's voltage limit at full output.
+    voltage_limit: f64,
+    /// The motor's current limit at full output.
+    current_limit: f64,
+    /// The scale last applied to the motor's limits.
+    scale: f64,
+    /// The hottest band the motor has ever reached, which is kept when it cools down.
+    reached: Option<usize>,
+}
+
+impl ManagedMotor {
+    /// The largest difference between two limits (in volts or amps) that is considered equal.
+    ///
+    /// Limits are stored by the motor in thousandths, so a written limit may read back
+    /// slightly differently.
+    const TOLERANCE: f64 = 0.002;
+
+    /// Reads the limits of a motor that hasn't been seen before.
+    fn new(motor: &Motor) -> Result<Self, MotorError> {
+        Ok(Self {
+            voltage_limit: Self::voltage_limit(motor)?,
+            current_limit: motor.current_limit()?,
+            scale: 1.0,
+            reached: None,
+        })
+    }
+
+    /// Returns the motor's voltage limit, or its maximum voltage if no limit has been set.
+    fn voltage_limit(motor: &Motor) -> Result<f64, MotorError> {
+        let limit = motor.voltage_limit()?;
+        Ok(if limit > 0.0 {
+            limit
+        } else {
+            motor.max_voltage()
+        })
+    }
+}
+
+impl ThermalManager {
+    /// The temperature (in degrees Celsius) at which VEXos stops a motor.
+    pub const SHUTDOWN_TEMPERATURE: f64 = 70.0;
+
+    /// The bands used by [`ThermalManager::default`].
+    pub const DEFAULT_BANDS: [ThermalBand; 3] = [
+        ThermalBand {
+            temperature: 45.0,
+            scale: 1.0,
+        },
+        ThermalBand {
+            temperature: 55.0,
+            scale: 0.75,
+        },
+        ThermalBand {
+            temperature: 65.0,
+            scale: 0.5,
+        },
+    ];
+
+    /// Creates a new thermal manager with the given bands.
+    ///
+    /// Motors below the coolest band keep their full limits. The bands do not need to be
+    /// sorted.
+    #[must_use]
+    pub fn new(bands: impl IntoIterator<Item = ThermalBand>) -> Self {
+        let mut bands: Vec<ThermalBand> = bands.into_iter().collect();
+        bands.sort_by(|a, b| a.temperature.total_cmp(&b.temperature));
+
+        Self {
+            bands,
+            motors: [None; 21],
+        }
+    }
+
+    /// Returns the manager's bands, sorted from coolest to hottest.
+    #[must_use]
+    pub fn bands(&self) -> &[ThermalBand] {
+        self.bands.as_slice()
+    }
+
+    /// Returns the fraction of its full output a motor at the given temperature may use.
+    #[must_use]
+    pub fn scale(&self, temperature: f64) -> f64 {
+        let Some(hottest) = self
+            .bands
+            .iter()
+            .rposition(|band| band.temperature <= temperature)
+        else {
+            return 1.0;
+        };
+
+        let band = self.bands[hottest];
+        let Some(next) = self.bands.get(hottest + 1) else {
+            return band.scale;
+        };
+
+        // Interpolate between the bands so that the limits drop gradually.
+        let t = (temperature - band.temperature) / (next.temperature - band.temperature);
+        (band.scale + (next.scale - band.scale) * t).clamp(0.0, 1.0)
+    }
+
+    /// Updates a motor's voltage and current limits for its current temperature.
+    ///
+    /// This should be called regularly for each motor, and returns a [`ThermalReport`]
+    /// describing the motor's thermal state. The report's `warning` flag is set on the
+    /// update where the motor first reaches a band hotter than any it has reached before, so
+    /// that drivers can be warned before the motor loses power.
+    ///
+    /// # Errors
+    ///
+    /// - A [`MotorError::Port`] error is returned if a motor device is not currently connected
+    ///   to the Smart Port.
+    pub fn update(&mut self, motor: &mut Motor) -> Result<ThermalReport, MotorError> {
+        let temperature = motor.temperature()?;
+        let scale = self.scale(temperature);
+
+        let port = motor.port_number();
+        let managed = match &mut self.motors[usize::from(port) - 1] {
+            Some(managed) => managed,
+            slot @ None => slot.insert(ManagedMotor::new(motor)?),
+        };
+
+        if (scale - managed.scale).abs() > f64::EPSILON {
+            // Adopt limits that were changed since they were last written as the new
+            // full limits.
+            let voltage_limit = ManagedMotor::voltage_limit(motor)?;
+            if (voltage_limit - managed.voltage_limit * managed.scale).abs()
+                > ManagedMotor::TOLERANCE
+            {
+                managed.voltage_limit = voltage_limit;
+            }
+
+            let current_limit = motor.current_limit()?;
+            if (current_limit - managed.current_limit * managed.scale).abs()
+                > ManagedMotor::TOLERANCE
+            {
+                managed.current_limit = current_limit;
+            }
+
+            motor.set_voltage_limit(managed.voltage_limit * scale)?;
+            motor.set_current_limit(managed.current_limit * scale)?;
+            managed.scale = scale;
+        }
+
+        let band = self
+            .bands
+            .iter()
+            .rposition(|band| band.temperature <= temperature);
+
+        let warning = band > managed.reached;
+        managed.reached = managed.reached.max(band);
+
+        Ok(ThermalReport {
+            port,
+            temperature,
+            headroom: (Self::SHUTDOWN_TEMPERATURE - temperature).max(0.0),
+            scale,
+            band,
+            warning,
+        })
+    }
+}
+
+impl Default for ThermalManager {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_BANDS)
+    }
+}
@@ -0,0 +1,194 @@
+//! Streams of motor fault and status changes.
+
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_core::Stream;
+use vex_sdk::{vexDeviceMotorFaultsGet, vexDeviceMotorFlagsGet, V5_DeviceT};
+
+use super::{Motor, MotorFaults, MotorStatus};
+use crate::smart::{validate_port, SmartDevice, SmartDeviceType};
+
+/// The health of a [`Motor`], as reported by [`MotorUpdates`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MotorHealth {
+    /// The motor is not connected to its Smart Port.
+    Disconnected,
+
+    /// The motor is connected.
+    Connected {
+        /// The motor's fault flags.
+        faults: MotorFaults,
+
+        /// The motor's status flags.
+        status: MotorStatus,
+    },
+}
+
+impl MotorHealth {
+    /// Returns the fault flags of the motor, or no flags if it is disconnected.
+    #[must_use]
+    pub const fn faults(&self) -> MotorFaults {
+        match self {
+            Self::Disconnected => MotorFaults::empty(),
+            Self::Connected { faults, .. } => *faults,
+        }
+    }
+
+    /// Returns `true` if the motor is connected.
+    #[must_use]
+    pub const fn is_connected(&self) -> bool {
+        matches!(self, Self::Connected { .. })
+    }
+}
+
+/// The number of times a motor has tripped each of its faults or been disconnected.
+///
+/// Kept by [`MotorUpdates`].
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct MotorFaultCounts {
+    /// The number of times [`MotorFaults::OVER_TEMPERATURE`] was set.
+    pub over_temperature: u32,
+
+    /// The number of times [`MotorFaults::OVER_CURRENT`] was set.
+    pub over_current: u32,
+
+    /// The number of times [`MotorFaults::DRIVER_FAULT`] was set.
+    pub driver_fault: u32,
+
+    /// The number of times [`MotorFaults::DRIVER_OVER_CURRENT`] was set.
+    pub driver_over_current: u32,
+
+    /// The number of times the motor was disconnected.
+    pub disconnects: u32,
+}
+
+impl MotorFaultCounts {
+    /// Returns `true` if the motor has never tripped a fault or been disconnected.
+    #[must_use]
+    pub const fn is_clean(&self) -> bool {
+        self.over_temperature == 0
+            && self.over_current == 0
+            && self.driver_fault == 0
+            && self.driver_over_current == 0
+            && self.disconnects == 0
+    }
+}
+
+/// A stream of changes to a motor's health.
+///
+/// See [`Motor::updates`] for more information.
+#[derive(Debug)]
+pub struct MotorUpdates {
+    port: u8,
+    device: V5_DeviceT,
+    last: Option<MotorHealth>,
+    counts: MotorFaultCounts,
+}
+
+// SAFETY: See the implementation for `Motor`. The device handle is only used to read flags.
+unsafe impl Send for MotorUpdates {}
+unsafe impl Sync for MotorUpdates {}
+
+impl MotorUpdates {
+    /// Returns the last health update.
+    ///
+    /// Returns `None` if the stream has not been polled yet.
+    #[must_use]
+    pub const fn last(&self) -> Option<MotorHealth> {
+        self.last
+    }
+
+    /// Returns the number of times the motor has tripped each of its faults or been
+    /// disconnected since the stream was created.
+    ///
+    /// Faults are only counted when the stream observes them, so faults that are set and
+    /// cleared between two polls of the stream will be missed.
+    #[must_use]
+    pub const fn counts(&self) -> MotorFaultCounts {
+        self.counts
+    }
+
+    /// Returns the port number of the motor.
+    #[must_use]
+    pub const fn port_number(&self) -> u8 {
+        self.port
+    }
+
+    fn health(&self) -> MotorHealth {
+        if validate_port(self.port, SmartDeviceType::Motor).is_err() {
+            return MotorHealth::Disconnected;
+        }
+
+        MotorHealth::Connected {
+            faults: MotorFaults::from_bits_retain(unsafe { vexDeviceMotorFaultsGet(self.device) }),
+            // Bits that aren't known status flags are dropped, so that they can't make the
+            // stream yield an update without any known flag changing.
+            status: MotorStatus::from_bits_truncate(unsafe { vexDeviceMotorFlagsGet(self.device) }),
+        }
+    }
+}
+
+impl Stream for MotorUpdates {
+    type Item = MotorHealth;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let current = this.health();
+
+        cx.waker().wake_by_ref();
+
+        if this.last == Some(current) {
+            return Poll::Pending;
+        }
+
+        if let Some(last) = this.last {
+            let counts = &mut this.counts;
+            let tripped = current.faults() & !last.faults();
+
+            for (fault, count) in [
+                (MotorFaults::OVER_TEMPERATURE, &mut counts.over_temperature),
+                (MotorFaults::OVER_CURRENT, &mut counts.over_current),
+                (MotorFaults::DRIVER_FAULT, &mut counts.driver_fault),
+                (
+                    MotorFaults::DRIVER_OVER_CURRENT,
+                    &mut counts.driver_over_current,
+                ),
+            ] {
+                if tripped.contains(fault) {
+                    *count += 1;
+                }
+            }
+
+            if last.is_connected() && !current.is_connected() {
+                counts.disconnects += 1;
+            }
+        }
+
+        this.last = Some(current);
+        Poll::Ready(Some(current))
+    }
+}
+
+impl Motor {
+    /// Returns an async stream of changes to the motor's health.
+    ///
+    /// Yields the motor's current [`MotorHealth`] when first polled, and thereafter whenever
+    /// its fault flags or status flags change, or it is disconnected or reconnected. The stream
+    /// also counts how many times each fault was tripped, which can be read with
+    /// [`MotorUpdates::counts`].
+    ///
+    /// The stream doesn't borrow the motor, so it can be moved into a separate task while the
+    /// motor is controlled elsewhere.
+    #[must_use]
+    pub fn updates(&self) -> MotorUpdates {
+        MotorUpdates {
+            port: self.port_number(),
+            device: self.device,
+            last: None,
+            counts: MotorFaultCounts::default(),
+        }
+    }
+}
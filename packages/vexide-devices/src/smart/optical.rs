@@ -31,7 +31,7 @@ use vex_sdk::{
     V5_DeviceT,
 };
 
-use super::{SmartDevice, SmartDeviceTimestamp, SmartDeviceType, SmartPort};
+use super::{telemetry::Telemetry, SmartDevice, SmartDeviceTimestamp, SmartDeviceType, SmartPort};
 use crate::PortError;
 
 /// An optical sensor plugged into a Smart Port.
@@ -530,6 +530,24 @@ impl SmartDevice for OpticalSensor {
         SmartDeviceType::Optical
     }
 }
+
+impl Telemetry for OpticalSensor {
+    const CHANNELS: &'static [&'static str] = &["hue_deg", "saturation", "brightness", "proximity"];
+
+    fn read_telemetry(&self, values: &mut [f64]) -> bool {
+        let (Ok(hue), Ok(saturation), Ok(brightness), Ok(proximity)) = (
+            self.hue(),
+            self.saturation(),
+            self.brightness(),
+            self.proximity(),
+        ) else {
+            return false;
+        };
+
+        values.copy_from_slice(&[hue, saturation, brightness, proximity]);
+        true
+    }
+}
 impl From<OpticalSensor> for SmartPort {
     fn from(device: OpticalSensor) -> Self {
         device.port
@@ -29,8 +29,11 @@ use vex_sdk::{
     vexDeviceAbsEncPositionSet, vexDeviceAbsEncStatusGet, vexDeviceAbsEncVelocityGet, V5_DeviceT,
 };
 
-use super::{motor::Direction, SmartDevice, SmartDeviceType, SmartPort};
-use crate::{position::Position, PortError};
+use super::{motor::Direction, telemetry::Telemetry, SmartDevice, SmartDeviceType, SmartPort};
+use crate::{
+    position::{AngularVelocity, Position},
+    PortError,
+};
 
 /// A rotation sensor plugged into a Smart Port.
 #[derive(Debug, PartialEq)]
@@ -49,6 +52,9 @@ pub struct RotationSensor {
 
     /// The raw position data recorded by the SDK at the time the sensor is reversed.
     raw_direction_offset: Position,
+
+    /// The number of times the sensor turns for each turn of the output shaft.
+    gear_ratio: f64,
 }
 
 // SAFETY: Required because we store a raw pointer to the device handle to avoid it getting from the
@@ -87,6 +93,7 @@ impl RotationSensor {
             direction,
             direction_offset: Position::default(),
             raw_direction_offset: Position::default(),
+            gear_ratio: 1.0,
         }
     }
 
@@ -137,9 +144,11 @@ impl RotationSensor {
     ///     _ = sensor.set_position(Position::from_degrees(15.0));
     /// }
     /// ```
-    pub fn set_position(&mut self, mut position: Position) -> Result<(), PortError> {
+    pub fn set_position(&mut self, position: Position) -> Result<(), PortError> {
         self.validate_port()?;
 
+        let mut position = Position::from_revolutions(position.as_revolutions() * self.gear_ratio);
+
         if self.direction == Direction::Reverse {
             position = -position;
         }
@@ -220,7 +229,7 @@ impl RotationSensor {
         //
         // For more information: <https://www.vexforum.com/t/rotation-sensor-bug-workaround-on-vexos-1-1-0/96577/2>
         if new_direction != self.direction() {
-            self.direction_offset = self.position()?;
+            self.direction_offset = self.sensor_position()?;
             self.raw_direction_offset = Position::from_ticks(
                 i64::from(unsafe { vexDeviceAbsEncPositionGet(self.device) }),
                 Self::TICKS_PER_REVOLUTION,
@@ -293,8 +302,53 @@ impl RotationSensor {
         self.direction
     }
 
+    /// Sets the external gear ratio between the sensor and the shaft it measures.
+    ///
+    /// The ratio is the number of times the sensor turns for each turn of the output shaft, so a
+    /// sensor on a 12-tooth gear meshed with a 36-tooth gear has a ratio of 3.0. Once set,
+    /// [`RotationSensor::position`], [`RotationSensor::set_position`], and
+    /// [`RotationSensor::velocity`] are all measured at the output shaft rather than the sensor.
+    /// [`RotationSensor::angle`] is always the angle of the sensor itself.
+    ///
+    /// By default, the ratio is 1.0.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `ratio` is not a finite number greater than zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use vexide::prelude::*;
+    ///
+    /// #[vexide::main]
+    /// async fn main(peripherals: Peripherals) {
+    ///     let mut sensor = RotationSensor::new(peripherals.port_1, Direction::Forward);
+    ///
+    ///     // The sensor is geared 3:1 to the shaft it measures.
+    ///     sensor.set_gear_ratio(3.0);
+    /// }
+    /// ```
+    pub fn set_gear_ratio(&mut self, ratio: f64) {
+        assert!(
+            ratio.is_finite() && ratio > 0.0,
+            "{ratio} is not a valid gear ratio. Gear ratios must be finite and greater than zero."
+        );
+        self.gear_ratio = ratio;
+    }
+
+    /// Returns the external gear ratio between the sensor and the shaft it measures.
+    ///
+    /// See [`RotationSensor::set_gear_ratio`] for more information.
+    #[must_use]
+    pub const fn gear_ratio(&self) -> f64 {
+        self.gear_ratio
+    }
+
     /// Returns the total number of degrees rotated by the sensor based on direction.
     ///
+    /// If an external gear ratio has been set with [`RotationSensor::set_gear_ratio`], this is the position of the output shaft.
+    ///
     /// # Errors
     ///
     /// An error is returned if an rotation sensor is not currently connected to the Smart Port.
@@ -317,6 +371,13 @@ impl RotationSensor {
     /// }
     /// ```
     pub fn position(&self) -> Result<Position, PortError> {
+        Ok(Position::from_revolutions(
+            self.sensor_position()?.as_revolutions() / self.gear_ratio,
+        ))
+    }
+
+    /// Returns the total number of degrees rotated by the sensor itself, ignoring the gear ratio.
+    fn sensor_position(&self) -> Result<Position, PortError> {
         self.validate_port()?;
 
         let mut delta_position = Position::from_ticks(
@@ -372,6 +433,8 @@ impl RotationSensor {
 
     /// Returns the sensor's current velocity in degrees per second.
     ///
+    /// If an external gear ratio has been set with [`RotationSensor::set_gear_ratio`], this is the velocity of the output shaft.
+    ///
     /// # Errors
     ///
     /// An error is returned if an rotation sensor is not currently connected to the Smart Port.
@@ -400,7 +463,20 @@ impl RotationSensor {
             raw_velocity *= -1;
         }
 
-        Ok(f64::from(raw_velocity) / 100.0)
+        Ok(f64::from(raw_velocity) / 100.0 / self.gear_ratio)
+    }
+
+    /// Returns the sensor's current angular velocity.
+    ///
+    /// If an external gear ratio has been set with [`RotationSensor::set_gear_ratio`], this is the velocity of the output shaft.
+    /// Unlike the degrees per second returned by [`RotationSensor::velocity`], this can be compared
+    /// directly with [`Motor::angular_velocity`](crate::smart::motor::Motor::angular_velocity).
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if an rotation sensor is not currently connected to the Smart Port.
+    pub fn angular_velocity(&self) -> Result<AngularVelocity, PortError> {
+        Ok(AngularVelocity::from_degrees_per_second(self.velocity()?))
     }
 
     /// Returns the sensor's internal status code.
@@ -439,6 +515,19 @@ impl SmartDevice for RotationSensor {
         SmartDeviceType::Rotation
     }
 }
+
+impl Telemetry for RotationSensor {
+    const CHANNELS: &'static [&'static str] = &["position_deg", "velocity_dps"];
+
+    fn read_telemetry(&self, values: &mut [f64]) -> bool {
+        let (Ok(position), Ok(velocity)) = (self.position(), self.velocity()) else {
+            return false;
+        };
+
+        values.copy_from_slice(&[position.as_degrees(), velocity]);
+        true
+    }
+}
 impl From<RotationSensor> for SmartPort {
     fn from(device: RotationSensor) -> Self {
         device.port
@@ -0,0 +1,289 @@
+//! Telemetry Recording
+//!
+//! This module provides a [`TelemetryRecorder`] for capturing readings from Smart Port devices
+//! at their update rate, which is useful for tuning controllers and diagnosing mechanisms after
+//! the fact.
+//!
+//! Samples are stored in a ring buffer that is allocated once when the recorder is created, so
+//! recording never allocates. Once the buffer is full, the oldest samples are overwritten.
+//! Recorded samples can be written as CSV to any [`Write`] implementation, such as a file on
+//! the SD card or [`stdout`](vexide_core::io::stdout), and can also be streamed to stdout as
+//! they are recorded.
+//!
+//! Any device implementing the [`Telemetry`] trait can be recorded. This includes motors and the
+//! rotation, inertial, distance, optical, and GPS sensors.
+//!
+//! # Examples
+//!
+//! Recording a flywheel's spin-up and saving it to the SD card:
+//!
+//! ```no_run
+//! use vexide::{devices::smart::telemetry::TelemetryRecorder, fs::File, prelude::*};
+//!
+//! #[vexide::main]
+//! async fn main(peripherals: Peripherals) {
+//!     let mut flywheel = Motor::new(peripherals.port_1, Gearset::Blue, Direction::Forward);
+//!     let mut recorder = TelemetryRecorder::new(500);
+//!
+//!     recorder.start();
+//!     flywheel.set_voltage(12.0).unwrap();
+//!
+//!     for _ in 0..300 {
+//!         recorder.update([&flywheel]);
+//!         sleep(Motor::UPDATE_INTERVAL).await;
+//!     }
+//!
+//!     recorder.stop();
+//!     recorder
+//!         .write_csv(File::create("flywheel.csv").unwrap())
+//!         .unwrap();
+//! }
+//! ```
+
+use alloc::vec::Vec;
+use core::{marker::PhantomData, time::Duration};
+
+use vexide_core::{
+    io::{self, Write},
+    println,
+    time::Instant,
+};
+
+use super::{SmartDevice, SmartDeviceTimestamp};
+
+/// The maximum number of values that can be recorded from a device in each sample.
+pub const MAX_CHANNELS: usize = 8;
+
+/// A Smart Port device that can be recorded by a [`TelemetryRecorder`].
+pub trait Telemetry: SmartDevice {
+    /// The names of the values recorded from the device, used as CSV column headers.
+    ///
+    /// There may be at most [`MAX_CHANNELS`] channels.
+    const CHANNELS: &'static [&'static str];
+
+    /// Reads the device's current value for each of its [`CHANNELS`](Telemetry::CHANNELS)
+    /// into `values`, in the same order.
+    ///
+    /// Returns `false` if the device couldn't be read from, in which case no sample is recorded.
+    fn read_telemetry(&self, values: &mut [f64]) -> bool;
+}
+
+/// A set of readings recorded from a device.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TelemetrySample {
+    /// The time since recording started.
+    pub elapsed: Duration,
+
+    /// The port number of the device.
+    pub port: u8,
+
+    /// The device's internal timestamp at the time of the sample, or `None` if it couldn't be
+    /// read.
+    pub timestamp: Option<SmartDeviceTimestamp>,
+
+    /// The recorded values, in the order of the device's [`CHANNELS`](Telemetry::CHANNELS).
+    ///
+    /// Only the first `CHANNELS.len()` values are used.
+    pub values: [f64; MAX_CHANNELS],
+}
+
+/// Records readings from devices into a fixed-size ring buffer.
+///
+/// See the [module-level documentation](self) for more information.
+#[derive(Debug)]
+pub struct TelemetryRecorder<D: Telemetry> {
+    samples: Vec<TelemetrySample>,
+    capacity: usize,
+    /// Where the next sample will be written once the buffer is full.
+    next: usize,
+    recording: bool,
+    streaming: bool,
+    started: Instant,
+    last_update: Option<Instant>,
+    _device: PhantomData<fn(&D)>,
+}
+
+impl<D: Telemetry> TelemetryRecorder<D> {
+    /// Creates a new recorder that holds up to `capacity` samples.
+    ///
+    /// The recorder starts out stopped.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if `D` has more than [`MAX_CHANNELS`] channels.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        assert!(
+            D::CHANNELS.len() <= MAX_CHANNELS,
+            "devices may record at most {MAX_CHANNELS} channels"
+        );
+
+        Self {
+            samples: Vec::with_capacity(capacity),
+            capacity,
+            next: 0,
+            recording: false,
+            streaming: false,
+            started: Instant::now(),
+            last_update: None,
+            _device: PhantomData,
+        }
+    }
+
+    /// Starts recording, clearing any previously recorded samples.
+    ///
+    /// If streaming is enabled, the CSV header is printed to stdout.
+    pub fn start(&mut self) {
+        self.clear();
+        self.recording = true;
+        self.started = Instant::now();
+
+        if self.streaming {
+            println!("{}", CsvHeader::<D>(PhantomData));
+        }
+    }
+
+    /// Stops recording. Recorded samples are kept until the next call to
+    /// [`start`](TelemetryRecorder::start) or [`clear`](TelemetryRecorder::clear).
+    pub const fn stop(&mut self) {
+        self.recording = false;
+    }
+
+    /// Returns `true` if the recorder is currently recording.
+    #[must_use]
+    pub const fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// Sets whether each sample is printed to stdout as a CSV row as it is recorded.
+    pub const fn set_streaming(&mut self, streaming: bool) {
+        self.streaming = streaming;
+    }
+
+    /// Removes all recorded samples.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        self.next = 0;
+        self.last_update = None;
+    }
+
+    /// Returns the number of recorded samples.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Returns `true` if no samples have been recorded.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Returns the maximum number of samples the recorder can hold.
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the recorded samples, from oldest to newest.
+    pub fn samples(&self) -> impl Iterator<Item = &TelemetrySample> {
+        let (newer, older) = self.samples.split_at(self.next);
+        older.iter().chain(newer)
+    }
+
+    /// Records a sample from each device if the recorder is recording.
+    ///
+    /// This can be called as often as needed, but only records once per `D::UPDATE_INTERVAL`,
+    /// since devices don't produce new readings any faster. Devices that can't be read from
+    /// are skipped.
+    pub fn update<'a>(&mut self, devices: impl IntoIterator<Item = &'a D>)
+    where
+        D: 'a,
+    {
+        if !self.recording || self.capacity == 0 {
+            return;
+        }
+
+        let now = Instant::now();
+        if self
+            .last_update
+            .is_some_and(|last| now - last < D::UPDATE_INTERVAL)
+        {
+            return;
+        }
+        self.last_update = Some(now);
+
+        for device in devices {
+            let mut values = [0.0; MAX_CHANNELS];
+            if !device.read_telemetry(&mut values[..D::CHANNELS.len()]) {
+                continue;
+            }
+
+            let sample = TelemetrySample {
+                elapsed: now - self.started,
+                port: device.port_number(),
+                timestamp: device.timestamp().ok(),
+                values,
+            };
+
+            if self.streaming {
+                println!("{}", CsvRow::<D>(&sample, PhantomData));
+            }
+
+            if self.samples.len() < self.capacity {
+                self.samples.push(sample);
+            } else {
+                self.samples[self.next] = sample;
+                self.next = (self.next + 1) % self.capacity;
+            }
+        }
+    }
+
+    /// Writes the recorded samples as CSV, from oldest to newest.
+    ///
+    /// The first row is a header containing the columns `time_ms`, `port`, `timestamp`, and
+    /// the names of the device's [`CHANNELS`](Telemetry::CHANNELS). The `timestamp` cell is
+    /// left empty for samples whose timestamp couldn't be read.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error that occurs while writing to `writer`.
+    pub fn write_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "{}", CsvHeader::<D>(PhantomData))?;
+        for sample in self.samples() {
+            writeln!(writer, "{}", CsvRow::<D>(sample, PhantomData))?;
+        }
+        writer.flush()
+    }
+}
+
+/// Formats the CSV header for a device's samples.
+struct CsvHeader<D>(PhantomData<D>);
+
+impl<D: Telemetry> core::fmt::Display for CsvHeader<D> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("time_ms,port,timestamp")?;
+        for channel in D::CHANNELS {
+            write!(f, ",{channel}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats a sample as a CSV row.
+struct CsvRow<'a, D>(&'a TelemetrySample, PhantomData<D>);
+
+impl<D: Telemetry> core::fmt::Display for CsvRow<'_, D> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let sample = self.0;
+        write!(f, "{},{},", sample.elapsed.as_millis(), sample.port)?;
+        // Missing timestamps are left as empty cells.
+        if let Some(timestamp) = sample.timestamp {
+            write!(f, "{}", timestamp.0)?;
+        }
+        for value in &sample.values[..D::CHANNELS.len()] {
+            write!(f, ",{value}")?;
+        }
+        Ok(())
+    }
+}
@@ -1,6 +1,9 @@
 //! This crate provides a procedural macro for marking the entrypoint of a [vexide](https://vexide.dev) program.
+//!
+//! It also provides the `#[vexide::test]` attribute and `vexide::test_main!()` macro for
+//! writing tests that run on the brain.
 
-use parse::{Attrs, MacroOpts};
+use parse::{Attrs, MacroOpts, TestAttrs, TestOpts};
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, ItemFn, Signature};
@@ -10,6 +13,9 @@ mod parse;
 const NO_SYNC_ERR: &str = "The vexide entrypoint must be marked `async`.";
 const NO_UNSAFE_ERR: &str = "The vexide entrypoint must be not marked `unsafe`.";
 const WRONG_ARGS_ERR: &str = "The vexide entrypoint must take a single parameter of type `vexide_devices::peripherals::Peripherals`";
+const TEST_NO_SYNC_ERR: &str = "vexide tests must be marked `async`.";
+const TEST_NO_UNSAFE_ERR: &str = "vexide tests must be not marked `unsafe`.";
+const TEST_WRONG_ARGS_ERR: &str = "vexide tests must take either no parameters or a single parameter of type `vexide_devices::peripherals::Peripherals`";
 
 fn verify_function_sig(sig: &Signature) -> Result<(), syn::Error> {
     let mut error = None;
@@ -43,6 +49,38 @@ fn verify_function_sig(sig: &Signature) -> Result<(), syn::Error> {
     }
 }
 
+fn verify_test_sig(sig: &Signature) -> Result<(), syn::Error> {
+    let mut error = None;
+
+    if sig.asyncness.is_none() {
+        let message = syn::Error::new_spanned(sig, TEST_NO_SYNC_ERR);
+        error.replace(message);
+    }
+    if sig.unsafety.is_some() {
+        let message = syn::Error::new_spanned(sig, TEST_NO_UNSAFE_ERR);
+        match error {
+            Some(ref mut e) => e.combine(message),
+            None => {
+                error.replace(message);
+            }
+        }
+    }
+    if sig.inputs.len() > 1 {
+        let message = syn::Error::new_spanned(sig, TEST_WRONG_ARGS_ERR);
+        match error {
+            Some(ref mut e) => e.combine(message),
+            None => {
+                error.replace(message);
+            }
+        }
+    }
+
+    match error {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
 fn make_code_sig(opts: MacroOpts) -> proc_macro2::TokenStream {
     let sig = if let Some(code_sig) = opts.code_sig {
         quote! { #code_sig }
@@ -86,11 +124,20 @@ fn make_entrypoint(inner: &ItemFn, opts: MacroOpts) -> proc_macro2::TokenStream
         quote! {}
     };
 
+    let crash_reports = if opts.crash_reports {
+        quote! {
+            ::vexide::panic::crash_report::set_enabled(true);
+        }
+    } else {
+        quote! {}
+    };
+
     quote! {
         #[no_mangle]
         unsafe extern "C" fn _start() -> ! {
             ::vexide::startup::startup();
             #banner_print
+            #crash_reports
 
             #inner
             let termination: #ret_type = ::vexide::runtime::block_on(
@@ -102,6 +149,54 @@ fn make_entrypoint(inner: &ItemFn, opts: MacroOpts) -> proc_macro2::TokenStream
     }
 }
 
+fn make_test(inner: &ItemFn, opts: TestOpts) -> proc_macro2::TokenStream {
+    match verify_test_sig(&inner.sig) {
+        Ok(()) => {}
+        Err(e) => return e.to_compile_error(),
+    }
+    let inner_ident = inner.sig.ident.clone();
+
+    // Each test is given its own copy of the peripherals, since they are moved into the test.
+    let args = if inner.sig.inputs.is_empty() {
+        quote! {}
+    } else {
+        quote! { unsafe { ::vexide::devices::peripherals::Peripherals::steal() } }
+    };
+
+    let timeout = if let Some(timeout) = opts.timeout {
+        quote! { ::core::option::Option::Some(#timeout) }
+    } else {
+        quote! { ::core::option::Option::None }
+    };
+
+    quote! {
+        #inner
+
+        const _: () = {
+            #[link_section = ".vexide_tests"]
+            #[used] // This is needed to prevent the linker from removing this object in release builds
+            static TEST: ::vexide::startup::test::Test = ::vexide::startup::test::Test {
+                name: ::core::concat!(::core::module_path!(), "::", ::core::stringify!(#inner_ident)),
+                timeout: #timeout,
+                run: || ::vexide::startup::test::__pin_test(#inner_ident(#args)),
+            };
+        };
+    }
+}
+
+fn make_test_entrypoint() -> proc_macro2::TokenStream {
+    quote! {
+        #[no_mangle]
+        unsafe extern "C" fn _start() -> ! {
+            ::vexide::startup::startup();
+            ::vexide::panic::set_hook(::vexide::startup::test::panic_hook);
+
+            ::vexide::runtime::block_on(::vexide::startup::test::run());
+            ::vexide::program::exit();
+        }
+    }
+}
+
 /// vexide's entrypoint macro
 ///
 /// Marks a function as the entrypoint for a vexide program. When the program is started,
@@ -117,6 +212,7 @@ fn make_entrypoint(inner: &ItemFn, opts: MacroOpts) -> proc_macro2::TokenStream
 ///
 /// - `banner`: Allows for disabling or using a custom banner theme. When `enabled = false` the banner will be disabled. `theme` can be set to a custom `BannerTheme` struct.
 /// - `code_sig`: Allows using a custom `CodeSignature` struct to configure program behavior.
+/// - `crash_reports`: When `crash_reports = true`, panic reports will be written to the SD card. This requires the `crash_reports` feature to be enabled.
 ///
 /// # Examples
 ///
@@ -176,6 +272,22 @@ fn make_entrypoint(inner: &ItemFn, opts: MacroOpts) -> proc_macro2::TokenStream
 ///    println!("Hello world!")
 /// }
 /// ```
+///
+/// Panic reports can be saved to the SD card so that they can be read after the brain is
+/// power-cycled:
+///
+/// ```ignore
+/// # #![no_std]
+/// # #![no_main]
+/// # use vexide::prelude::*;
+/// use vexide::panic::crash_report;
+/// #[vexide::main(crash_reports = true)]
+/// async fn main(_p: Peripherals) {
+///     if let Some(report) = crash_report::last() {
+///         println!("Last run crashed:\n{report}");
+///     }
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn main(attrs: TokenStream, item: TokenStream) -> TokenStream {
     let item = parse_macro_input!(item as ItemFn);
@@ -194,8 +306,94 @@ pub fn main(attrs: TokenStream, item: TokenStream) -> TokenStream {
     .into()
 }
 
+/// Marks a function as a test that runs on the brain.
+///
+/// Tests are collected and run by the test harness in `vexide::startup::test`, which is
+/// started by placing [`test_main!()`](test_main) in the test binary. See that module for
+/// more information on how tests are run and reported.
+///
+/// Test functions must be marked `async` and must not be marked `unsafe`. They may either
+/// take no arguments or a single argument of type `Peripherals`, in which case each test is
+/// given its own `Peripherals` instance.
+///
+/// # Parameters
+///
+/// - `timeout`: The longest a test may run for before it fails, as a `Duration`. If this is
+///   not specified, the harness's default timeout is used.
+///
+/// # Examples
+///
+/// ```ignore
+/// # #![no_std]
+/// # #![no_main]
+/// # use vexide::prelude::*;
+/// use core::time::Duration;
+///
+/// vexide::test_main!();
+///
+/// #[vexide::test]
+/// async fn arithmetic() {
+///     assert_eq!(2 + 2, 4);
+/// }
+///
+/// #[vexide::test(timeout = Duration::from_secs(5))]
+/// async fn controller_connected(peripherals: Peripherals) {
+///     assert!(peripherals.primary_controller.is_connected());
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn test(attrs: TokenStream, item: TokenStream) -> TokenStream {
+    let item = parse_macro_input!(item as ItemFn);
+    let opts = TestOpts::from(parse_macro_input!(attrs as TestAttrs));
+
+    make_test(&item, opts).into()
+}
+
+/// Creates the entrypoint for a binary containing tests.
+///
+/// Instead of calling a `main` function, the program runs every function marked with
+/// [`#[vexide::test]`](macro@test) and prints the results to stdout. This replaces
+/// `#[vexide::main]`, so the two can't be used in the same binary.
+///
+/// The test harness reports panicking tests using a panic hook, so the `panic` feature of
+/// `vexide` must be enabled.
+///
+/// # Examples
+///
+/// ```ignore
+/// #![no_std]
+/// #![no_main]
+///
+/// vexide::test_main!();
+///
+/// #[vexide::test]
+/// async fn arithmetic() {
+///     assert_eq!(2 + 2, 4);
+/// }
+/// ```
+#[proc_macro]
+pub fn test_main(input: TokenStream) -> TokenStream {
+    parse_macro_input!(input as syn::parse::Nothing);
+
+    let entrypoint = make_test_entrypoint();
+    let code_signature = make_code_sig(MacroOpts::default());
+
+    quote! {
+        const _: () = {
+            #code_signature
+
+            #entrypoint
+        };
+    }
+    .into()
+}
+
 #[cfg(test)]
 mod test {
+    // Explicitly import the built-in `#[test]` attribute, since the glob import below
+    // would otherwise make it ambiguous with the `test` macro defined in this crate.
+    use core::prelude::v1::test;
+
     use syn::Ident;
 
     use super::*;
@@ -247,6 +445,7 @@ mod test {
                 banner_enabled: false,
                 banner_theme: None,
                 code_sig: None,
+                crash_reports: false,
             },
         );
         assert!(!entrypoint.to_string().contains("banner"));
@@ -257,6 +456,7 @@ mod test {
                 banner_enabled: true,
                 banner_theme: None,
                 code_sig: None,
+                crash_reports: false,
             },
         );
         assert!(entrypoint.to_string().contains("banner"));
@@ -271,6 +471,7 @@ mod test {
                 "__custom_code_sig_ident__",
                 proc_macro2::Span::call_site(),
             )),
+            crash_reports: false,
         });
 
         println!("{}", code_sig.to_string());
@@ -279,6 +480,30 @@ mod test {
         ));
     }
 
+    #[test]
+    fn enables_crash_reports_using_parsed_opts() {
+        let source = quote! {
+            async fn main(_peripherals: Peripherals) {
+                println!("Hello, world!");
+            }
+        };
+        let input = syn::parse2::<ItemFn>(source.clone()).unwrap();
+
+        let entrypoint = make_entrypoint(&input, MacroOpts::default());
+        assert!(!entrypoint.to_string().contains("crash_report"));
+
+        let entrypoint = make_entrypoint(
+            &input,
+            MacroOpts {
+                crash_reports: true,
+                ..MacroOpts::default()
+            },
+        );
+        assert!(entrypoint
+            .to_string()
+            .contains(":: vexide :: panic :: crash_report :: set_enabled (true) ;"));
+    }
+
     #[test]
     fn requires_async() {
         let source = quote! {
@@ -334,4 +559,85 @@ mod test {
 
         assert!(output.to_string().contains(WRONG_ARGS_ERR));
     }
+
+    #[test]
+    fn registers_test_fn() {
+        let source = quote! {
+            async fn adds() {
+                assert_eq!(1 + 1, 2);
+            }
+        };
+
+        let input = syn::parse2::<ItemFn>(source.clone()).unwrap();
+        let output = make_test(&input, TestOpts::default());
+
+        assert_eq!(
+            output.to_string(),
+            quote! {
+                #source
+
+                const _: () = {
+                    #[link_section = ".vexide_tests"]
+                    #[used]
+                    static TEST: ::vexide::startup::test::Test = ::vexide::startup::test::Test {
+                        name: ::core::concat!(::core::module_path!(), "::", ::core::stringify!(adds)),
+                        timeout: ::core::option::Option::None,
+                        run: || ::vexide::startup::test::__pin_test(adds()),
+                    };
+                };
+            }
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn passes_peripherals_and_timeout_to_test_fn() {
+        let source = quote! {
+            async fn spins(_peripherals: Peripherals) {}
+        };
+
+        let input = syn::parse2::<ItemFn>(source).unwrap();
+        let output = make_test(
+            &input,
+            TestOpts {
+                timeout: Some(syn::parse_quote!(Duration::from_secs(5))),
+            },
+        )
+        .to_string();
+
+        assert!(output.contains(
+            "spins (unsafe { :: vexide :: devices :: peripherals :: Peripherals :: steal () })"
+        ));
+        assert!(output
+            .contains("timeout : :: core :: option :: Option :: Some (Duration :: from_secs (5))"));
+    }
+
+    #[test]
+    fn test_requires_async_and_safe() {
+        let source = quote! {
+            fn adds() {}
+        };
+        let input = syn::parse2::<ItemFn>(source).unwrap();
+        let output = make_test(&input, TestOpts::default());
+        assert!(output.to_string().contains(TEST_NO_SYNC_ERR));
+
+        let source = quote! {
+            async unsafe fn adds() {}
+        };
+        let input = syn::parse2::<ItemFn>(source).unwrap();
+        let output = make_test(&input, TestOpts::default());
+        assert!(output.to_string().contains(TEST_NO_UNSAFE_ERR));
+    }
+
+    #[test]
+    fn test_disallows_2_args() {
+        let source = quote! {
+            async fn spins(_peripherals: Peripherals, _other: Peripherals) {}
+        };
+
+        let input = syn::parse2::<ItemFn>(source).unwrap();
+        let output = make_test(&input, TestOpts::default());
+
+        assert!(output.to_string().contains(TEST_WRONG_ARGS_ERR));
+    }
 }
@@ -4,7 +4,7 @@ use syn::{
     parenthesized,
     parse::{Parse, ParseStream},
     punctuated::Punctuated,
-    token, Ident, LitBool, Result, Token,
+    token, Expr, Ident, LitBool, Result, Token,
 };
 
 mod kw {
@@ -16,6 +16,10 @@ mod kw {
     custom_keyword!(theme);
 
     custom_keyword!(code_sig);
+
+    custom_keyword!(crash_reports);
+
+    custom_keyword!(timeout);
 }
 
 #[derive(Clone)]
@@ -23,6 +27,7 @@ pub struct MacroOpts {
     pub banner_enabled: bool,
     pub banner_theme: Option<Ident>,
     pub code_sig: Option<Ident>,
+    pub crash_reports: bool,
 }
 
 impl Default for MacroOpts {
@@ -31,6 +36,7 @@ impl Default for MacroOpts {
             banner_enabled: true,
             banner_theme: None,
             code_sig: None,
+            crash_reports: false,
         }
     }
 }
@@ -53,6 +59,9 @@ impl From<Attrs> for MacroOpts {
                     }
                 }
                 Attribute::CodeSig(code_sig) => opts.code_sig = Some(code_sig.into_ident()),
+                Attribute::CrashReports(crash_reports) => {
+                    opts.crash_reports = crash_reports.as_bool();
+                }
             }
         }
         opts
@@ -74,6 +83,7 @@ impl Parse for Attrs {
 pub enum Attribute {
     Banner(Banner),
     CodeSig(CodeSig),
+    CrashReports(CrashReports),
 }
 
 impl Parse for Attribute {
@@ -83,6 +93,8 @@ impl Parse for Attribute {
             input.parse().map(Attribute::Banner)
         } else if lookahead.peek(kw::code_sig) {
             input.parse().map(Attribute::CodeSig)
+        } else if lookahead.peek(kw::crash_reports) {
+            input.parse().map(Attribute::CrashReports)
         } else {
             Err(lookahead.error())
         }
@@ -230,6 +242,110 @@ impl ToTokens for CodeSig {
     }
 }
 
+pub struct CrashReports {
+    token: kw::crash_reports,
+    eq: Token![=],
+    value: LitBool,
+}
+
+impl CrashReports {
+    pub const fn as_bool(&self) -> bool {
+        self.value.value
+    }
+}
+
+impl Parse for CrashReports {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        Ok(Self {
+            token: input.parse()?,
+            eq: input.parse()?,
+            value: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for CrashReports {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.token.to_tokens(tokens);
+        self.eq.to_tokens(tokens);
+        self.value.to_tokens(tokens);
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct TestOpts {
+    pub timeout: Option<Expr>,
+}
+
+impl From<TestAttrs> for TestOpts {
+    fn from(value: TestAttrs) -> Self {
+        let mut opts = Self::default();
+        for attr in value.attr_list {
+            match attr {
+                TestAttribute::Timeout(timeout) => opts.timeout = Some(timeout.into_expr()),
+            }
+        }
+        opts
+    }
+}
+
+pub struct TestAttrs {
+    attr_list: Punctuated<TestAttribute, Token![,]>,
+}
+
+impl Parse for TestAttrs {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        Ok(Self {
+            attr_list: Punctuated::parse_terminated(input)?,
+        })
+    }
+}
+
+pub enum TestAttribute {
+    Timeout(Timeout),
+}
+
+impl Parse for TestAttribute {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        let lookahead = input.lookahead1();
+        if lookahead.peek(kw::timeout) {
+            input.parse().map(TestAttribute::Timeout)
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+pub struct Timeout {
+    token: kw::timeout,
+    eq: Token![=],
+    value: Expr,
+}
+
+impl Timeout {
+    pub fn into_expr(self) -> Expr {
+        self.value
+    }
+}
+
+impl Parse for Timeout {
+    fn parse(input: ParseStream<'_>) -> Result<Self> {
+        Ok(Self {
+            token: input.parse()?,
+            eq: input.parse()?,
+            value: input.parse()?,
+        })
+    }
+}
+
+impl ToTokens for Timeout {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.token.to_tokens(tokens);
+        self.eq.to_tokens(tokens);
+        self.value.to_tokens(tokens);
+    }
+}
+
 #[cfg(test)]
 mod test {
     use quote::quote;
@@ -279,6 +395,22 @@ mod test {
         assert_eq!(opts.code_sig.unwrap().to_string(), "my_code_sig");
     }
 
+    #[test]
+    fn parses_crash_reports_attribute() {
+        let source = quote! {
+            crash_reports = true
+        };
+        let input = syn::parse2::<CrashReports>(source).unwrap();
+        assert!(input.as_bool());
+
+        let source = quote! {
+            banner(enabled = false), crash_reports = true
+        };
+        let opts = MacroOpts::from(syn::parse2::<Attrs>(source).unwrap());
+        assert!(!opts.banner_enabled);
+        assert!(opts.crash_reports);
+    }
+
     #[test]
     fn macro_opts_defaults_when_n_opts_missing() {
         fn macro_opts_from(source: TokenStream) -> MacroOpts {
@@ -290,6 +422,7 @@ mod test {
         let opts = macro_opts_from(source);
         assert!(opts.banner_enabled);
         assert_eq!(opts.code_sig, None);
+        assert!(!opts.crash_reports);
 
         let source = quote! {
             banner(enabled = false)
@@ -312,4 +445,21 @@ mod test {
         assert!(!opts.banner_enabled);
         assert_eq!(opts.code_sig.unwrap().to_string(), "my_code_sig");
     }
+
+    #[test]
+    fn parses_test_attrs_into_test_opts() {
+        let opts = TestOpts::from(syn::parse2::<TestAttrs>(quote! {}).unwrap());
+        assert!(opts.timeout.is_none());
+
+        let source = quote! {
+            timeout = Duration::from_secs(5)
+        };
+        let opts = TestOpts::from(syn::parse2::<TestAttrs>(source).unwrap());
+        assert_eq!(
+            opts.timeout.unwrap().to_token_stream().to_string(),
+            "Duration :: from_secs (5)"
+        );
+
+        assert!(syn::parse2::<TestAttrs>(quote! { banner(enabled = false) }).is_err());
+    }
 }
@@ -0,0 +1,135 @@
+//! Panic notifications on the primary controller.
+//!
+//! The brain's screen is often hidden inside the robot, so drivers might not notice
+//! that the program has panicked. When the `controller_panics` feature is enabled, the
+//! default panic hook writes the location and a truncated version of the panic message
+//! to the primary controller's screen and plays a distinctive rumble pattern.
+
+use core::{fmt, panic::PanicInfo, time::Duration};
+
+use vex_sdk::{
+    vexControllerConnectionStatusGet, vexControllerTextSet, V5_ControllerId, V5_ControllerStatus,
+};
+use vexide_core::time::Instant;
+
+/// The number of characters that fit on a line of the controller's screen.
+const MAX_COLUMNS: usize = 19;
+
+/// The line that rumble patterns are written to.
+const RUMBLE_LINE: u32 = 4;
+
+/// The rumble pattern played when the program panics: three short, three long, then two
+/// short rumbles.
+///
+/// This is as close to SOS as fits in the controller's limit of 8 rumble symbols.
+const RUMBLE_PATTERN: &[u8] = b"...---..";
+
+/// How long to wait between writes to the controller.
+///
+/// The controller only accepts a new line of text every 50ms when connected over VEXnet,
+/// and writes sent before then are dropped.
+const WRITE_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A line of text for the controller's screen.
+///
+/// Text past the end of the line is discarded, and characters that the controller can't
+/// display are replaced.
+struct Line {
+    buf: [u8; MAX_COLUMNS * 2],
+    len: usize,
+    max_len: usize,
+}
+
+impl Line {
+    const fn new(max_len: usize) -> Self {
+        Self {
+            buf: [0; MAX_COLUMNS * 2],
+            len: 0,
+            max_len,
+        }
+    }
+
+    fn push(&mut self, byte: u8) {
+        if self.len < self.max_len {
+            self.buf[self.len] = byte;
+            self.len += 1;
+        }
+    }
+}
+
+impl fmt::Write for Line {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            match c {
+                '\n' | '\t' => self.push(b' '),
+                c if c.is_ascii_control() => {}
+                c if c.is_ascii() => self.push(c as u8),
+                _ => self.push(b'?'),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes a line of text to the primary controller, then waits for the controller to be
+/// ready to accept more.
+fn write_line(line: u32, text: &[u8]) {
+    let mut buf = [0; MAX_COLUMNS + 1];
+    buf[..text.len()].copy_from_slice(text);
+
+    unsafe {
+        vexControllerTextSet(
+            u32::from(V5_ControllerId::kControllerMaster.0),
+            line,
+            0,
+            buf.as_ptr().cast(),
+        );
+    }
+
+    let start = Instant::now();
+    while start.elapsed() < WRITE_INTERVAL {
+        unsafe {
+            vex_sdk::vexTasksRun();
+        }
+    }
+}
+
+/// Writes the panic's location and message to the primary controller's screen and
+/// rumbles the controller.
+///
+/// This does nothing if the primary controller is not connected. Since the controller
+/// only accepts updates at a limited rate, this function takes a few hundred
+/// milliseconds to complete.
+pub(crate) fn notify(info: &PanicInfo<'_>) {
+    use fmt::Write;
+
+    if unsafe { vexControllerConnectionStatusGet(V5_ControllerId::kControllerMaster) }
+        == V5_ControllerStatus::kV5ControllerOffline
+    {
+        return;
+    }
+
+    let mut header = Line::new(MAX_COLUMNS);
+    _ = write!(header, "PANIC");
+    if let Some(location) = info.location() {
+        // Only the file name (not its directory) will fit on the screen.
+        let file = location
+            .file()
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or_default();
+        _ = write!(header, " {file}:{}", location.line());
+    }
+
+    // The message is wrapped onto the remaining two lines.
+    let mut message = Line::new(MAX_COLUMNS * 2);
+    _ = write!(message, "{}", info.message());
+    let (first, second) = message.buf[..message.len].split_at(message.len.min(MAX_COLUMNS));
+
+    write_line(0, b"");
+    write_line(1, &header.buf[..header.len]);
+    write_line(2, first);
+    write_line(3, second);
+    write_line(RUMBLE_LINE, RUMBLE_PATTERN);
+}
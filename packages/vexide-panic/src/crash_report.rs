@@ -0,0 +1,217 @@
+//! Panic reports persisted to the SD card.
+//!
+//! When enabled, the default panic hook writes a report containing the panic message,
+//! its location, a backtrace, and some information about the state of the robot (uptime,
+//! competition mode, and battery) to [`CRASH_REPORT_PATH`] on the SD card. Since this
+//! file survives the brain being power-cycled, it can be used to find out what went
+//! wrong in a match where no computer was connected to the robot.
+//!
+//! Reports are disabled by default and can be turned on using [`set_enabled`] or the
+//! `crash_reports` option of the `#[vexide::main]` attribute.
+//!
+//! # Examples
+//!
+//! Printing (and then clearing) the report left behind by the last run of the program:
+//!
+//! ```
+//! use vexide::panic::crash_report;
+//!
+//! if let Some(report) = crash_report::last() {
+//!     println!("The last run of this program crashed:\n{report}");
+//!     crash_report::clear().unwrap();
+//! }
+//! ```
+
+use alloc::string::String;
+use core::{
+    ffi::CStr,
+    fmt::{self, Write},
+    panic::PanicInfo,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use vexide_core::{
+    backtrace::Backtrace,
+    competition::{self, CompetitionMode},
+    fs, time,
+};
+
+/// The file on the SD card that crash reports are written to.
+///
+/// Only the report from the most recent panic is kept.
+pub const CRASH_REPORT_PATH: &str = "crash_report.txt";
+
+/// [`CRASH_REPORT_PATH`] as a C string, for opening the report without allocating.
+const CRASH_REPORT_PATH_C: &CStr = c"crash_report.txt";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Enables or disables writing crash reports to the SD card when the program panics.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns `true` if crash reports will be written to the SD card when the program panics.
+#[must_use]
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Returns the contents of the last crash report saved to the SD card, if there is one.
+///
+/// This will return `None` if no report exists, if the report has been cleared using
+/// [`clear`], or if the SD card could not be read.
+#[must_use]
+pub fn last() -> Option<String> {
+    fs::read_to_string(CRASH_REPORT_PATH)
+        .ok()
+        .filter(|report| !report.is_empty())
+}
+
+/// Clears the last crash report saved to the SD card.
+///
+/// VEXos does not support deleting files, so this truncates the report file rather than
+/// removing it.
+///
+/// # Errors
+///
+/// This function will error if the report file could not be opened for writing. See
+/// [`File::create`](fs::File::create) for more information.
+pub fn clear() -> vexide_core::io::Result<()> {
+    fs::write(CRASH_REPORT_PATH, [])
+}
+
+/// Writes a crash report for the given panic to the SD card, replacing the previous one.
+///
+/// Like [`crash_log::save`](vexide_core::crash_log::save), this talks to the filesystem
+/// directly and formats the report into a buffer on the stack, so it doesn't allocate and
+/// can be called before anything else in the panic hook that might. The backtrace is added
+/// afterwards using [`append_backtrace`].
+///
+/// Any errors are ignored, since there is nowhere left to report them to.
+pub(crate) fn save(info: &PanicInfo<'_>) {
+    if !is_enabled() {
+        return;
+    }
+
+    unsafe {
+        if vex_sdk::vexFileMountSD() != vex_sdk::FRESULT::FR_OK {
+            return;
+        }
+
+        let fd = vex_sdk::vexFileOpenCreate(CRASH_REPORT_PATH_C.as_ptr());
+        if fd.is_null() {
+            return;
+        }
+
+        let mut writer = ReportWriter::new(fd);
+        _ = write_report(&mut writer, info);
+        writer.flush();
+
+        vex_sdk::vexFileClose(fd);
+    }
+}
+
+/// Adds a backtrace to the end of the crash report written by [`save`].
+///
+/// This is kept separate from [`save`] because capturing a backtrace allocates, so the rest
+/// of the report is already on the SD card if the capture fails.
+pub(crate) fn append_backtrace(backtrace: &Backtrace) {
+    if !is_enabled() || backtrace.frames.is_empty() {
+        return;
+    }
+
+    unsafe {
+        let fd = vex_sdk::vexFileOpenWrite(CRASH_REPORT_PATH_C.as_ptr());
+        if fd.is_null() {
+            return;
+        }
+
+        let mut writer = ReportWriter::new(fd);
+        _ = writeln!(writer);
+        _ = writeln!(writer, "{backtrace}");
+        writer.flush();
+
+        vex_sdk::vexFileClose(fd);
+    }
+}
+
+fn write_report(w: &mut impl Write, info: &PanicInfo<'_>) -> fmt::Result {
+    writeln!(w, "{info}")?;
+    writeln!(w)?;
+
+    let uptime = time::uptime();
+    writeln!(
+        w,
+        "uptime: {}.{:03}s",
+        uptime.as_secs(),
+        uptime.subsec_millis()
+    )?;
+
+    let mode = match competition::mode() {
+        CompetitionMode::Disabled => "disabled",
+        CompetitionMode::Autonomous => "autonomous",
+        CompetitionMode::Driver => "driver",
+    };
+    if let Some(system) = competition::system() {
+        writeln!(w, "competition mode: {mode} ({system:?})")?;
+    } else {
+        writeln!(w, "competition mode: {mode} (not connected)")?;
+    }
+
+    let (capacity, voltage, current, temperature) = unsafe {
+        (
+            vex_sdk::vexBatteryCapacityGet() as u8,
+            f64::from(vex_sdk::vexBatteryVoltageGet()) / 1000.0,
+            f64::from(vex_sdk::vexBatteryCurrentGet()) / 1000.0,
+            vex_sdk::vexBatteryTemperatureGet() as u64,
+        )
+    };
+    writeln!(
+        w,
+        "battery: {capacity}% ({voltage:.2} V, {current:.2} A, {temperature} °C)"
+    )
+}
+
+/// Formats text into a fixed-size buffer on the stack, writing it to a file whenever the
+/// buffer fills up.
+struct ReportWriter {
+    fd: *mut vex_sdk::FIL,
+    buf: [u8; 256],
+    len: usize,
+}
+
+impl ReportWriter {
+    const fn new(fd: *mut vex_sdk::FIL) -> Self {
+        Self {
+            fd,
+            buf: [0; 256],
+            len: 0,
+        }
+    }
+
+    /// Writes the buffered text to the file.
+    fn flush(&mut self) {
+        if self.len > 0 {
+            unsafe {
+                vex_sdk::vexFileWrite(self.buf.as_mut_ptr().cast(), 1, self.len as _, self.fd);
+            }
+            self.len = 0;
+        }
+    }
+}
+
+impl Write for ReportWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for chunk in s.as_bytes().chunks(self.buf.len()) {
+            if self.len + chunk.len() > self.buf.len() {
+                self.flush();
+            }
+
+            self.buf[self.len..self.len + chunk.len()].copy_from_slice(chunk);
+            self.len += chunk.len();
+        }
+
+        Ok(())
+    }
+}
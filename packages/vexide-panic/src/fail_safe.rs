@@ -0,0 +1,152 @@
+//! Fail-safe actuator shutdown on panic.
+//!
+//! When a program panics, any motors that were running keep the last output they were
+//! given until the program is stopped. With the `fail_safe` feature enabled, the panic
+//! handler puts the robot into a safe state before running the panic hook:
+//!
+//! - All Smart Motors (on every port, whether or not they were created by the program)
+//!   are set to coast with 0V of output.
+//! - All ADI motors (on the brain and on any ADI expanders) are set to zero output.
+//! - All PWM outputs are set to a neutral pulse width of [`PWM_NEUTRAL`], which stops ESCs
+//!   and centers servos.
+//! - Digital outputs (such as pneumatic solenoids) that have been given a safe state
+//!   using [`set_safe_state`] are set to that state. Other digital outputs are left
+//!   untouched.
+//!
+//! This behavior can be turned off at runtime using [`set_enabled`]. Custom panic hooks
+//! can also put the robot into a safe state themselves by calling [`engage`].
+//!
+//! # Examples
+//!
+//! ```
+//! use vexide::{panic::fail_safe, prelude::*};
+//!
+//! #[vexide::main]
+//! async fn main(peripherals: Peripherals) {
+//!     let claw = AdiDigitalOut::new(peripherals.adi_a);
+//!
+//!     // Open the claw if the program panics.
+//!     fail_safe::set_safe_state(&claw, LogicLevel::Low);
+//! }
+//! ```
+
+use core::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+
+use vex_sdk::{
+    vexDeviceAdiPortConfigGet, vexDeviceAdiValueSet, vexDeviceGetByIndex, vexDeviceGetStatus,
+    vexDeviceMotorBrakeModeSet, vexDeviceMotorVoltageSet, V5MotorBrakeMode,
+    V5_AdiPortConfiguration, V5_DeviceT, V5_DeviceType, V5_MAX_DEVICE_PORTS,
+};
+use vexide_devices::adi::{digital::LogicLevel, AdiDevice, AdiDigitalOut};
+
+/// The number of devices that can have ADI ports (21 Smart Ports and the brain's onboard ADI).
+const ADI_DEVICES: usize = 22;
+
+/// The number of ADI ports on each device.
+const ADI_PORTS: u32 = 8;
+
+/// The raw value that PWM outputs are set to when the robot is put into a safe state.
+///
+/// Unlike ADI motors, the raw value of a PWM output is its pulse width rather than its
+/// power, so 0 sends the shortest pulse (roughly 0.94ms). To an ESC or servo, that means
+/// full reverse or full travel. This value is the midpoint of the 0-255 range (roughly
+/// 1.5ms), which is neutral instead.
+pub const PWM_NEUTRAL: i32 = 128;
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Bitmasks of the digital outputs with a configured safe state on each ADI device.
+static SAFE_STATE_PORTS: [AtomicU8; ADI_DEVICES] = [const { AtomicU8::new(0) }; ADI_DEVICES];
+
+/// Bitmasks of the safe state (set for high, clear for low) of each ADI device's digital outputs.
+static SAFE_STATE_LEVELS: [AtomicU8; ADI_DEVICES] = [const { AtomicU8::new(0) }; ADI_DEVICES];
+
+/// Enables or disables putting the robot into a safe state when the program panics.
+///
+/// This is enabled by default.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Returns `true` if the robot will be put into a safe state when the program panics.
+#[must_use]
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Returns the index into the safe state bitmasks and the bit for a digital output.
+fn safe_state_bit(device: &AdiDigitalOut) -> (usize, u8) {
+    let expander = device.expander_port_number().unwrap_or(ADI_DEVICES as u8);
+    let port = device.port_numbers()[0];
+
+    (usize::from(expander - 1), 1 << (port - 1))
+}
+
+/// Sets the level that a digital output will be set to when the program panics.
+///
+/// This is typically used to release pneumatics (or hold them in place) so that a
+/// mechanism can't move unexpectedly while the robot is stopped.
+pub fn set_safe_state(device: &AdiDigitalOut, level: LogicLevel) {
+    let (index, bit) = safe_state_bit(device);
+
+    if level.is_high() {
+        SAFE_STATE_LEVELS[index].fetch_or(bit, Ordering::Relaxed);
+    } else {
+        SAFE_STATE_LEVELS[index].fetch_and(!bit, Ordering::Relaxed);
+    }
+    SAFE_STATE_PORTS[index].fetch_or(bit, Ordering::Relaxed);
+}
+
+/// Removes the safe state of a digital output, leaving it untouched when the program panics.
+pub fn clear_safe_state(device: &AdiDigitalOut) {
+    let (index, bit) = safe_state_bit(device);
+    SAFE_STATE_PORTS[index].fetch_and(!bit, Ordering::Relaxed);
+}
+
+/// Puts the robot into a safe state.
+///
+/// This is called automatically by the panic handler (unless disabled with [`set_enabled`]),
+/// but may also be called from a custom panic hook. This function does not allocate.
+pub fn engage() {
+    let mut device_types: [V5_DeviceType; V5_MAX_DEVICE_PORTS] = unsafe { core::mem::zeroed() };
+    unsafe {
+        vexDeviceGetStatus(device_types.as_mut_ptr());
+    }
+
+    for (index, device_type) in device_types.into_iter().enumerate() {
+        let device = unsafe { vexDeviceGetByIndex(index as u32) };
+
+        match device_type {
+            V5_DeviceType::kDeviceTypeMotorSensor => unsafe {
+                vexDeviceMotorBrakeModeSet(device, V5MotorBrakeMode::kV5MotorBrakeModeCoast);
+                vexDeviceMotorVoltageSet(device, 0);
+            },
+            V5_DeviceType::kDeviceTypeAdiSensor if index < ADI_DEVICES => {
+                engage_adi(device, index);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn engage_adi(device: V5_DeviceT, index: usize) {
+    let safe_ports = SAFE_STATE_PORTS[index].load(Ordering::Relaxed);
+    let safe_levels = SAFE_STATE_LEVELS[index].load(Ordering::Relaxed);
+
+    for port in 0..ADI_PORTS {
+        let value = match unsafe { vexDeviceAdiPortConfigGet(device, port) } {
+            V5_AdiPortConfiguration::kAdiPortTypeLegacyPwm
+            | V5_AdiPortConfiguration::kAdiPortTypeLegacyPwmSlew => 0,
+            // PWM outputs use this port type.
+            V5_AdiPortConfiguration::kAdiPortTypeAnalogOut => PWM_NEUTRAL,
+            V5_AdiPortConfiguration::kAdiPortTypeDigitalOut if safe_ports & (1 << port) != 0 => {
+                i32::from(safe_levels & (1 << port) != 0)
+            }
+            _ => continue,
+        };
+
+        unsafe {
+            vexDeviceAdiValueSet(device, port, value);
+        }
+    }
+}
@@ -3,11 +3,30 @@
 //! Supports capturing and printing backtraces to aid in debugging.
 //!
 //! If the `display_panics` feature is enabled, it will also display the panic message on the V5 Brain display.
+//!
+//! If the `crash_log` feature is enabled, the program's most recent output will be dumped
+//! alongside the panic message and saved to the SD card.
+//!
+//! If the `crash_reports` feature is enabled, panic reports can be persisted to the SD card
+//! and read back on the next boot. See the [`crash_report`] module for more information.
+//!
+//! If the `controller_panics` feature is enabled, the panic message will also be shown on the
+//! primary controller's screen, along with a rumble to alert the driver.
+//!
+//! If the `fail_safe` feature is enabled, all motors will be stopped before the panic is
+//! reported. See the [`fail_safe`] module for more information.
 
 #![no_std]
 
 extern crate alloc;
 
+#[cfg(feature = "controller_panics")]
+mod controller;
+#[cfg(feature = "crash_reports")]
+pub mod crash_report;
+#[cfg(feature = "fail_safe")]
+pub mod fail_safe;
+
 use alloc::{
     boxed::Box,
     string::{String, ToString},
@@ -32,10 +51,15 @@ static FIRST_PANIC: AtomicBool = AtomicBool::new(true);
 /// panic messages graphically before exiting.
 #[cfg(feature = "display_panics")]
 fn draw_error(display: &mut Display, msg: &str, backtrace: &Backtrace) {
+    use vexide_core::backtrace::symbol_name;
+
     const ERROR_BOX_MARGIN: i16 = 16;
     const ERROR_BOX_PADDING: i16 = 16;
     const LINE_HEIGHT: i16 = 20;
     const LINE_MAX_WIDTH: usize = 52;
+    /// The number of lines that fit in the error box.
+    const MAX_LINES: i16 =
+        (Display::VERTICAL_RESOLUTION - 2 * (ERROR_BOX_MARGIN + ERROR_BOX_PADDING)) / LINE_HEIGHT;
 
     fn draw_text(screen: &mut Display, buffer: &str, line: i16) {
         screen.draw_text(
@@ -52,6 +76,58 @@ fn draw_error(display: &mut Display, msg: &str, backtrace: &Backtrace) {
         );
     }
 
+    /// Draws the last `max_lines` lines of the crash log to the display.
+    ///
+    /// This avoids allocating by drawing directly through the SDK rather than
+    /// constructing [`Text`] objects.
+    #[cfg(feature = "crash_log")]
+    fn draw_crash_log(line: i16, max_lines: usize) {
+        use vexide_core::crash_log;
+
+        // Copy the log out of the ring buffer so that it can be split into lines.
+        let mut contents = [0u8; crash_log::CAPACITY];
+        let mut len = 0;
+        crash_log::dump(|chunk| {
+            contents[len..len + chunk.len()].copy_from_slice(chunk);
+            len += chunk.len();
+        });
+
+        let lines = contents[..len]
+            .split(|&byte| byte == b'\n')
+            .filter(|text| !text.is_empty());
+        let skipped = lines.clone().count().saturating_sub(max_lines);
+
+        unsafe {
+            vex_sdk::vexDisplayForegroundColor(0xff_ff_ff);
+            vex_sdk::vexDisplayFontNamedSet(c"monospace".as_ptr());
+            vex_sdk::vexDisplayTextSize(1, 4);
+        }
+
+        for (i, text) in lines.skip(skipped).enumerate() {
+            // NUL-terminated copy of the line, with control characters (and any
+            // interior NULs) replaced so that they don't mess up the output.
+            let mut buffer = [0u8; LINE_MAX_WIDTH + 1];
+            for (dst, &src) in buffer.iter_mut().zip(text.iter().take(LINE_MAX_WIDTH)) {
+                *dst = if src.is_ascii_control() { b' ' } else { src };
+            }
+
+            unsafe {
+                vex_sdk::vexDisplayPrintf(
+                    i32::from(ERROR_BOX_MARGIN + ERROR_BOX_PADDING),
+                    i32::from(
+                        ERROR_BOX_MARGIN
+                            + ERROR_BOX_PADDING
+                            + ((line + i as i16) * LINE_HEIGHT)
+                            + Display::HEADER_HEIGHT,
+                    ),
+                    0,
+                    c"%s".as_ptr(),
+                    buffer.as_ptr(),
+                );
+            }
+        }
+    }
+
     display.set_render_mode(vexide_devices::display::RenderMode::Immediate);
 
     let error_box_rect = Rect::new(
@@ -93,7 +169,41 @@ fn draw_error(display: &mut Display, msg: &str, backtrace: &Backtrace) {
     draw_text(display, "stack backtrace:", line);
     line += 1;
 
-    if !backtrace.frames.is_empty() {
+    if backtrace
+        .frames
+        .iter()
+        .any(|frame| symbol_name(*frame).is_some())
+    {
+        // Function names are too long to fit multiple frames on a line, so only draw as many
+        // frames as fit in the error box, leaving room to note how many were left out.
+        let frames = &backtrace.frames;
+        let available = usize::try_from(MAX_LINES - line).unwrap_or(0);
+        let shown = if frames.len() > available {
+            available.saturating_sub(1)
+        } else {
+            frames.len()
+        };
+
+        for (i, frame) in frames[..shown].iter().enumerate() {
+            let mut msg = String::new();
+            match symbol_name(*frame) {
+                Some(name) => write!(msg, "{i:>3}: {name}").unwrap(),
+                None => write!(msg, "{i:>3}: {frame:?}").unwrap(),
+            }
+            if let Some((end, _)) = msg.char_indices().nth(LINE_MAX_WIDTH) {
+                msg.truncate(end);
+            }
+            draw_text(display, &msg, line);
+            line += 1;
+        }
+
+        if shown < frames.len() && available > 0 {
+            let mut msg = String::new();
+            write!(msg, "{} more frames...", frames.len() - shown).unwrap();
+            draw_text(display, &msg, line);
+            line += 1;
+        }
+    } else if !backtrace.frames.is_empty() {
         const ROW_LENGTH: usize = 3;
         for (col, frames) in backtrace.frames.chunks(ROW_LENGTH).enumerate() {
             let mut msg = String::new();
@@ -104,6 +214,46 @@ fn draw_error(display: &mut Display, msg: &str, backtrace: &Backtrace) {
             line += 1;
         }
     }
+
+    #[cfg(feature = "crash_log")]
+    {
+        // Leave a blank line and a header above the log, and only draw it if at
+        // least one line of output will fit in the remaining space.
+        if line + 3 <= MAX_LINES {
+            line += 1;
+            draw_text(display, "recent output:", line);
+            line += 1;
+
+            draw_crash_log(line, (MAX_LINES - line) as usize);
+        }
+    }
+}
+
+/// Dumps the crash log to stdout and the SD card.
+///
+/// This is done before anything else in the default panic hook, since nothing here
+/// allocates and it will still work if the panic was caused by running out of memory.
+#[cfg(feature = "crash_log")]
+fn dump_crash_log(info: &core::panic::PanicInfo<'_>) {
+    use vexide_core::{
+        crash_log,
+        io::{stdout, Stdout, Write},
+    };
+
+    if let Some(mut stdout) = stdout().try_lock() {
+        _ = stdout.write_all(b"recent output:\n");
+        crash_log::dump(|chunk| {
+            // Writes larger than the serial buffer would be truncated.
+            for part in chunk.chunks(Stdout::INTERNAL_BUFFER_SIZE) {
+                _ = stdout.write_all(part);
+            }
+        });
+        _ = stdout.write_all(b"\n");
+    }
+
+    // Include the panic message itself in the copy saved to the SD card.
+    crash_log::record(format_args!("{info}"));
+    _ = crash_log::save();
 }
 
 /// The default panic handler.
@@ -116,6 +266,18 @@ fn draw_error(display: &mut Display, msg: &str, backtrace: &Backtrace) {
 /// `display_panics` feature is enabled, it will also display the panic message
 /// on the V5 Brain display.
 ///
+/// If the `crash_log` feature is enabled, the contents of the
+/// [crash log](vexide_core::crash_log) (the program's most recent output) are
+/// dumped to the serial connection, written to the SD card, and shown beneath
+/// the backtrace on the display.
+///
+/// If the `controller_panics` feature is enabled, the panic's location and a truncated
+/// version of its message are written to the primary controller's screen, and the
+/// controller is rumbled.
+///
+/// If the `crash_reports` feature is enabled and reports have been turned on with
+/// [`crash_report::set_enabled`], a report of the panic is also written to the SD card.
+///
 /// Note that if `display_panics` is not enabled, this function will not return.
 /// It will immediately exit the program after printing the panic message. If
 /// you do not want this behavior, you should use your own
@@ -135,10 +297,25 @@ fn draw_error(display: &mut Display, msg: &str, backtrace: &Backtrace) {
 /// });
 /// ```
 pub fn default_panic_hook(info: &core::panic::PanicInfo<'_>) {
+    #[cfg(feature = "crash_log")]
+    dump_crash_log(info);
+
+    // The report is saved before anything that allocates, in case the panic was caused by
+    // running out of memory.
+    #[cfg(feature = "crash_reports")]
+    crash_report::save(info);
+
     println!("{info}");
 
+    if vexide_core::stack::is_overflowed() {
+        println!("note: the stack has overflowed, so program memory may be corrupted");
+    }
+
     let backtrace = Backtrace::capture();
 
+    #[cfg(feature = "crash_reports")]
+    crash_report::append_backtrace(&backtrace);
+
     #[cfg(feature = "display_panics")]
     draw_error(
         &mut unsafe { Display::new() },
@@ -150,6 +327,9 @@ pub fn default_panic_hook(info: &core::panic::PanicInfo<'_>) {
         println!("{backtrace}");
     }
 
+    #[cfg(feature = "controller_panics")]
+    controller::notify(info);
+
     #[cfg(not(feature = "display_panics"))]
     vexide_core::program::exit();
 }
@@ -252,6 +432,13 @@ pub fn panic(info: &core::panic::PanicInfo<'_>) -> ! {
         vexide_core::program::exit();
     }
 
+    // Stop the robot before doing anything else, since rendering the panic report
+    // (or a custom hook) may take a while.
+    #[cfg(feature = "fail_safe")]
+    if fail_safe::is_enabled() {
+        fail_safe::engage();
+    }
+
     // Try to lock the HOOK mutex. If we can't, we'll just use the default panic
     // handler, since it's probably not good to panic in the panic handler and
     // leave the user clueless about what happened.
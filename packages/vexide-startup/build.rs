@@ -1,6 +1,81 @@
 #![allow(missing_docs)]
 
+use std::{env, fs, path::PathBuf};
+
+/// The environment variable used to configure the size of the stack.
+const STACK_SIZE_VAR: &str = "VEXIDE_STACK_SIZE";
+
+/// The default size of the stack (4 MiB).
+const DEFAULT_STACK_SIZE: u64 = 0x40_0000;
+
+/// The smallest allowed stack size (64 KiB).
+const MIN_STACK_SIZE: u64 = 0x1_0000;
+
+/// The size of the memory region shared by the program, heap, and stack (66 MiB).
+///
+/// This must match `__program_ram_length` in `link/v5.ld`.
+const PROGRAM_RAM_LENGTH: u64 = 0x0420_0000;
+
+/// Parses a size in bytes, written in either decimal or hexadecimal (prefixed with `0x`)
+/// and optionally followed by a `K` or `M` suffix for kibibytes or mebibytes.
+fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim().replace('_', "");
+
+    let (digits, multiplier) = if let Some(digits) = value.strip_suffix(['K', 'k']) {
+        (digits, 1024)
+    } else if let Some(digits) = value.strip_suffix(['M', 'm']) {
+        (digits, 1024 * 1024)
+    } else {
+        (value.as_str(), 1)
+    };
+
+    let size = if let Some(hex) = digits.strip_prefix("0x") {
+        u64::from_str_radix(hex, 16).ok()?
+    } else {
+        digits.parse().ok()?
+    };
+
+    size.checked_mul(multiplier)
+}
+
+/// Reads the configured stack size, failing the build if it is invalid.
+fn stack_size() -> u64 {
+    println!("cargo:rerun-if-env-changed={STACK_SIZE_VAR}");
+
+    let Ok(value) = env::var(STACK_SIZE_VAR) else {
+        return DEFAULT_STACK_SIZE;
+    };
+
+    let Some(size) = parse_size(&value) else {
+        panic!("{STACK_SIZE_VAR} must be a size in bytes (such as `8M` or `0x800000`), found `{value}`");
+    };
+
+    assert!(
+        size % 8 == 0,
+        "{STACK_SIZE_VAR} must be a multiple of 8 bytes, found {size:#x}"
+    );
+    assert!(
+        size >= MIN_STACK_SIZE,
+        "{STACK_SIZE_VAR} must be at least {MIN_STACK_SIZE:#x} bytes, found {size:#x}"
+    );
+    assert!(
+        size < PROGRAM_RAM_LENGTH,
+        "{STACK_SIZE_VAR} must be smaller than the {PROGRAM_RAM_LENGTH:#x} bytes of memory available to the program, found {size:#x}"
+    );
+
+    size
+}
+
 fn main() {
     let manifest_dir = env!("CARGO_MANIFEST_DIR");
     println!("cargo:rustc-link-search=native={manifest_dir}/link");
+
+    // Generate the memory layout included by `link/v5.ld`.
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    fs::write(
+        out_dir.join("vexide_memory.ld"),
+        format!("__stack_length = {:#x};\n", stack_size()),
+    )
+    .unwrap();
+    println!("cargo:rustc-link-search=native={}", out_dir.display());
 }
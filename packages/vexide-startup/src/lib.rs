@@ -10,13 +10,38 @@
 //!   the startup process by clearing the `.bss` section (which stores uninitialized data)
 //!   and initializing vexide's heap allocator.
 //!
-//! This crate does NOT provide a `libc` [crt0 implementation]. No `libc`-style global
-//! constructors are called. This means that the [`__libc_init_array`] function must be
-//! explicitly called if you wish to link to C libraries.
+//! - Tests written with `#[vexide::test]` are run on the brain by the harness in the
+//!   [`test`] module.
+//!
+//! This crate does NOT provide a `libc` [crt0 implementation]. By default, no `libc`-style
+//! global constructors are called. If you wish to link to C or C++ libraries that rely on
+//! static constructors, either enable the `global_constructors` feature (which runs the
+//! `.preinit_array` and `.init_array` sections in [`startup`] and the `.fini_array` section
+//! on exit), or explicitly call the [`__libc_init_array`] function yourself.
 //!
 //! [crt0 implementation]: https://en.wikipedia.org/wiki/Crt0
 //! [`__libc_init_array`]: https://maskray.me/blog/2021-11-07-init-ctors-init-array
 //!
+//! # Memory Layout
+//!
+//! The stack is placed at the end of the program's memory region, and is 4 MiB large by
+//! default. All memory between the end of the program and the bottom of the stack is used
+//! as heap space, along with the memory used by the patcher once it has finished running.
+//!
+//! The size of the stack can be changed by setting the `VEXIDE_STACK_SIZE` environment
+//! variable when building, which gives more or less of the remaining memory to the heap.
+//! Sizes can be written in bytes (in decimal or hexadecimal) or with a `K` or `M` suffix,
+//! and must be a multiple of 8 bytes. This is typically done in your project's
+//! `.cargo/config.toml` file:
+//!
+//! ```toml
+//! [env]
+//! VEXIDE_STACK_SIZE = "8M"
+//! ```
+//!
+//! The build will fail if the stack size is invalid, or if the program is too large to fit
+//! in memory alongside the stack.
+//!
 //! # Example
 //!
 //! This is an example of a minimal user program that boots without using the main vexide
@@ -60,9 +85,12 @@
 
 #![no_std]
 
+extern crate alloc;
+
 pub mod banner;
 mod code_signature;
 mod patcher;
+pub mod test;
 
 pub use code_signature::{CodeSignature, ProgramFlags, ProgramOwner, ProgramType};
 
@@ -83,6 +111,37 @@ unsafe extern "C" {
 
     static mut __bss_start: u32;
     static mut __bss_end: u32;
+
+    #[cfg(feature = "global_constructors")]
+    static __preinit_array_start: Option<unsafe extern "C" fn()>;
+    #[cfg(feature = "global_constructors")]
+    static __preinit_array_end: Option<unsafe extern "C" fn()>;
+
+    #[cfg(feature = "global_constructors")]
+    static __init_array_start: Option<unsafe extern "C" fn()>;
+    #[cfg(feature = "global_constructors")]
+    static __init_array_end: Option<unsafe extern "C" fn()>;
+}
+
+/// Calls each function in an array of global constructors, in order.
+///
+/// # Safety
+///
+/// `start..end` must be a valid array of function pointers that are safe to call.
+#[cfg(all(target_vendor = "vex", feature = "global_constructors"))]
+unsafe fn run_constructors(
+    start: *const Option<unsafe extern "C" fn()>,
+    end: *const Option<unsafe extern "C" fn()>,
+) {
+    let mut entry = start;
+    while entry < end {
+        unsafe {
+            if let Some(constructor) = *entry {
+                constructor();
+            }
+            entry = entry.add(1);
+        }
+    }
 }
 
 // Include the first-stage assembly entrypoint. This routine contains the first
@@ -99,8 +158,12 @@ core::arch::global_asm!(include_str!("./boot.S"));
 /// This function does the following initialization:
 ///
 /// - Fills the `.bss` (uninitialized statics) section with zeroes.
+/// - Paints the unused part of the stack so that its usage can be measured (see
+///   [`vexide_core::stack`]).
 /// - Sets up the global heap allocator if the `allocator` feature is specified.
 /// - Applies [differential upload patches] to the program if a patch file exists in memory.
+/// - Runs C/C++ global constructors (the `.preinit_array` and `.init_array` sections) if the
+///   `global_constructors` feature is specified.
 ///
 /// [differential upload patches]: https://vexide.dev/docs/building-uploading/#uploading-strategies
 ///
@@ -119,6 +182,10 @@ pub unsafe fn startup() {
             (&raw mut __bss_end).offset_from(&raw mut __bss_start) as usize,
         );
 
+        // Fill the stack with a known pattern to allow for measuring stack usage and
+        // detecting stack overflows.
+        vexide_core::stack::paint();
+
         // Initialize the heap allocator in our heap region defined in the linkerscript
         #[cfg(feature = "allocator")]
         vexide_core::allocator::claim(&raw mut __heap_start, &raw mut __heap_end);
@@ -132,5 +199,16 @@ pub unsafe fn startup() {
         // Reclaim 6mb memory region occupied by patches and program copies as heap space.
         #[cfg(feature = "allocator")]
         vexide_core::allocator::claim(&raw mut __patcher_ram_start, &raw mut __patcher_ram_end);
+
+        // Run global constructors from linked C/C++ code now that static data and the heap
+        // are ready to use. Their destructors are run by `vexide_core::program::exit`.
+        #[cfg(feature = "global_constructors")]
+        {
+            run_constructors(
+                &raw const __preinit_array_start,
+                &raw const __preinit_array_end,
+            );
+            run_constructors(&raw const __init_array_start, &raw const __init_array_end);
+        }
     }
 }
@@ -0,0 +1,55 @@
+//! CRC-32 checksums for verifying patches.
+//!
+//! This is the common CRC-32 variant (polynomial `0x04C11DB7`, reflected, used by zlib, PNG,
+//! and Ethernet), so checksums can be computed on the host using most CRC-32 libraries.
+
+/// The reflected CRC-32 polynomial.
+const POLYNOMIAL: u32 = 0xEDB8_8320;
+
+/// Lookup table of the CRC of every possible byte, generated at compile time.
+static TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+
+        table[i] = crc;
+        i += 1;
+    }
+
+    table
+};
+
+/// Computes the CRC-32 checksum of `data`.
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0;
+    for &byte in data {
+        crc = (crc >> 8) ^ TABLE[((crc ^ u32::from(byte)) & 0xFF) as usize];
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(crc32(b""), 0);
+    }
+}
@@ -1,6 +1,8 @@
+use crc32::crc32;
 use varint_encoding::VarIntReader;
 use vexide_core::io::{Cursor, Read, Seek, SeekFrom};
 
+mod crc32;
 mod varint_encoding;
 
 // Assembly implementation of the patch overwriter (`__patcher_overwrite`).
@@ -97,16 +99,54 @@ enum PatcherState {
 ///   of invalidating and cleaning instruction caches is described to further detail in
 ///   [ARM's documentation](https://developer.arm.com/documentation/den0013/latest/Caches/Invalidating-and-cleaning-cache-memory).
 ///
+/// ## Integrity checks
+///
+/// If the base binary on the brain isn't the one the patch was built against (for example, after an
+/// interrupted upload), applying the patch would silently produce a corrupted program. To catch this,
+/// newer patches include CRC-32 checksums of both the base binary and the new binary it should produce.
+/// The base binary is verified before the patch is applied, and the new binary is verified before the
+/// overwriter is run. If either check fails, the patcher panics rather than jumping into the result.
+///
+/// Patches in the older format don't carry checksums, so they are applied without these checks.
+///
+/// # Patch Format
+///
+/// Patch files begin with a header of little-endian `u32` values, followed by the patch data
+/// produced by `bidiff`:
+///
+/// | Offset | Contents                                       |
+/// |--------|------------------------------------------------|
+/// | `0`    | Magic (`0xB1DF`)                               |
+/// | `4`    | Patch format version (`0x1000` or `0x1001`)    |
+/// | `8`    | Length of the patch file, including the header |
+/// | `12`   | Length of the base binary                      |
+/// | `16`   | Length of the new binary                       |
+/// | `20`   | CRC-32 checksum of the base binary (`0x1001`)  |
+/// | `24`   | CRC-32 checksum of the new binary (`0x1001`)   |
+///
+/// Version `0x1000` patches end their header after the length of the new binary, while version
+/// `0x1001` patches include both checksums. Patches with any other version are ignored.
+///
+/// The offsets of the binary lengths are also relied on by the `_boot` and `__patcher_overwrite`
+/// assembly routines.
+///
+/// # Panics
+///
+/// Panics if a version `0x1001` patch's base binary or built binary do not match its checksums.
+///
 /// # Safety
 ///
-/// The caller must ensure that the patch loaded at 0x07A00000 has been built using the currently running
-/// binary as the basis for the patch.
+/// The caller must ensure that the patch loaded at 0x07A00000 was uploaded by a tool that produces
+/// patches in the format described above.
 pub(crate) unsafe fn patch() {
     // First four bytes of the patch file MUST be 0xB1DF for the patch to be applied.
     const PATCH_MAGIC: u32 = 0xB1DF;
 
-    // Next four bytes of the patch have to match this version identifier for the patch to be applied.
-    const PATCH_VERSION: u32 = 0x1000;
+    // Patch format version without checksums, as written by older versions of cargo-v5.
+    const PATCH_VERSION_UNCHECKED: u32 = 0x1000;
+
+    // Patch format version with CRC-32 checksums of the base and new binaries.
+    const PATCH_VERSION_CHECKED: u32 = 0x1001;
 
     /// Load address of patch files.
     const PATCH: *mut u32 = &raw mut __patcher_patch_start;
@@ -114,34 +154,56 @@ pub(crate) unsafe fn patch() {
     unsafe {
         // First few bytes contain some important metadata we'll need to setup the patch.
         let patch_magic = PATCH.read(); // Should be 0xB1DF if the patch needs to be applied.
-        let patch_version = PATCH.offset(1).read(); // Shoud be 0x1000
+        let patch_version = PATCH.offset(1).read(); // Should be 0x1000 or 0x1001
         let patch_len = PATCH.offset(2).read(); // length of the patch buffer
         let base_binary_len = PATCH.offset(3).read(); // length of the currently running binary
         let new_binary_len = PATCH.offset(4).read(); // length of the new binary after the patch
 
+        // Number of `u32` metadata values at the start of the patch.
+        let header_len = match patch_version {
+            PATCH_VERSION_UNCHECKED => 5,
+            PATCH_VERSION_CHECKED => 7,
+            _ => 0,
+        };
+
         // Do not proceed with  patch if:
         // - We have an unexpected PATCH_MAGIC (We later change this magic to 0xB2DF to intentionally
         //   trigger this check in order to break the patcher ouf of an infinite loop).
-        // - Our patch format version does not match the version in the patch.
+        // - We don't understand the patch format version.
         // - There isn't anything to patch.
-        if patch_magic != PATCH_MAGIC || patch_version != PATCH_VERSION || patch_len == 0 {
+        if patch_magic != PATCH_MAGIC || header_len == 0 || patch_len == 0 {
             return;
         }
 
+        // Checksums of the binary the patch was built against and the new binary after the patch,
+        // if the patch has them.
+        let checksums = (patch_version == PATCH_VERSION_CHECKED)
+            .then(|| (PATCH.offset(5).read(), PATCH.offset(6).read()));
+
         // Change patch magic to something invalid so we don't re-apply the patch next time.
         PATCH.write(0xB2DF);
 
         // Slice of the copy of user program memory we made in vexide's `_boot` routine before any
         // Rust code had the chance to modify `.bss` or `.data`. This is our base binary.
-        let base = Cursor::new(core::slice::from_raw_parts(
+        let base = core::slice::from_raw_parts(
             (&raw mut __patcher_base_start).cast(),
             base_binary_len as usize,
-        ));
+        );
 
-        // Slice of our patch contents. We offset by 20 bytes to skip the metadata inserted by cargo-v5 and bidiff.
+        // Make sure that the patch was built against the program that's actually on the brain,
+        // since applying it to anything else would produce garbage.
+        if let Some((base_binary_crc, _)) = checksums {
+            let found_crc = crc32(base);
+            assert!(
+                found_crc == base_binary_crc,
+                "Differential upload failed: the program on the brain (checksum {found_crc:#010x}) is not the one this patch was built for (checksum {base_binary_crc:#010x}). Try uploading the program again without differential uploads."
+            );
+        }
+
+        // Slice of our patch contents. We offset past the header to skip the metadata inserted by cargo-v5 and bidiff.
         let patch = core::slice::from_raw_parts(
-            PATCH.offset(5).cast(),
-            patch_len as usize - (size_of::<u32>() * 5),
+            PATCH.add(header_len).cast(),
+            patch_len as usize - (size_of::<u32>() * header_len),
         );
 
         // This is a 2mb slice of uninitialized memory that we've reserved for building the new binary in.
@@ -151,7 +213,16 @@ pub(crate) unsafe fn patch() {
         );
 
         // Build the new binary using `base` and `patch` as a reference.
-        bipatch(base, patch, new);
+        bipatch(Cursor::new(base), patch, new);
+
+        // Refuse to run the new binary if it didn't come out as expected.
+        if let Some((_, new_binary_crc)) = checksums {
+            let found_crc = crc32(new);
+            assert!(
+                found_crc == new_binary_crc,
+                "Differential upload failed: the patched program (checksum {found_crc:#010x}) does not match the uploaded program (checksum {new_binary_crc:#010x}). Try uploading the program again without differential uploads."
+            );
+        }
 
         // Jump to the stage 2 overwriter routine to handle the rest.
         core::arch::asm!("b __patcher_overwrite", options(noreturn));
@@ -0,0 +1,231 @@
+//! On-target test harness.
+//!
+//! Since the V5 Brain has no `libtest`, tests that need to run on real hardware are written
+//! using the `#[vexide::test]` attribute and run by the harness in this module. Test
+//! functions must be `async`, and may optionally take a `Peripherals` argument.
+//!
+//! Tests are placed in their own binary (usually an integration test with `harness = false`)
+//! whose entrypoint is created with the `vexide::test_main!()` macro:
+//!
+//! ```toml
+//! # Cargo.toml
+//! [[test]]
+//! name = "drivetrain"
+//! harness = false
+//! ```
+//!
+//! ```ignore
+//! // tests/drivetrain.rs
+//! #![no_std]
+//! #![no_main]
+//!
+//! use core::time::Duration;
+//!
+//! use vexide::prelude::*;
+//!
+//! vexide::test_main!();
+//!
+//! #[vexide::test]
+//! async fn arithmetic() {
+//!     assert_eq!(2 + 2, 4);
+//! }
+//!
+//! #[vexide::test(timeout = Duration::from_secs(5))]
+//! async fn motor_spins(peripherals: Peripherals) {
+//!     let mut motor = Motor::new(peripherals.port_1, Gearset::Green, Direction::Forward);
+//!     motor.set_voltage(6.0).unwrap();
+//!     sleep(Duration::from_secs(1)).await;
+//!     assert!(motor.velocity().unwrap() > 0.0);
+//! }
+//! ```
+//!
+//! Running `cargo test --test drivetrain` then uploads and runs the tests using the runner
+//! configured in `.cargo/config.toml` (typically `cargo v5 run --file`).
+//!
+//! # Behavior
+//!
+//! Tests are run one at a time, sorted by name. Each test is given a fresh [`Peripherals`]
+//! instance, so devices created by one test should not be relied on by the next. A test
+//! fails if it panics or doesn't finish within its timeout ([`DEFAULT_TIMEOUT`] unless
+//! specified). Tasks spawned by a test keep running after the test finishes.
+//!
+//! Since vexide programs are compiled with `panic = "abort"`, a panicking test can't be
+//! recovered from. When a test panics, the failure is reported and the remaining tests
+//! are counted as not run.
+//!
+//! # Output Format
+//!
+//! Results are printed to stdout, one line at a time:
+//!
+//! ```text
+//! running 3 tests
+//! test drivetrain::arithmetic ... ok
+//! test drivetrain::motor_spins ... FAILED (timed out after 5s)
+//! test drivetrain::odometry ... FAILED (panicked at tests/drivetrain.rs:32:5: assertion failed: x > 0.0)
+//! test result: FAILED. 1 passed; 2 failed; 0 not run
+//! ```
+//!
+//! Newlines in panic messages are escaped as `\n` so that each result fits on one line.
+//!
+//! [`Peripherals`]: https://docs.rs/vexide-devices/latest/vexide_devices/peripherals/struct.Peripherals.html
+
+use alloc::{boxed::Box, string::ToString};
+use core::{
+    future::{poll_fn, Future},
+    panic::PanicInfo,
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::Poll,
+    time::Duration,
+};
+
+use vexide_core::{println, sync::Mutex, time::Instant};
+
+/// How long a test may run for if it doesn't specify a timeout.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A future that runs a test.
+pub type TestFuture = Pin<Box<dyn Future<Output = ()>>>;
+
+/// A test registered with `#[vexide::test]`.
+///
+/// The attribute places one of these in the program's `.vexide_tests` linker section,
+/// where it is found by [`run`].
+#[derive(Debug, Clone, Copy)]
+pub struct Test {
+    /// The full path of the test function.
+    pub name: &'static str,
+    /// How long the test may run for before failing, or `None` to use [`DEFAULT_TIMEOUT`].
+    pub timeout: Option<Duration>,
+    /// Creates the test's future.
+    pub run: fn() -> TestFuture,
+}
+
+/// Boxes a test future. Used by `#[vexide::test]`.
+#[doc(hidden)]
+pub fn __pin_test(future: impl Future<Output = ()> + 'static) -> TestFuture {
+    Box::pin(future)
+}
+
+/// The name of the test that is currently running.
+static CURRENT_TEST: Mutex<Option<&'static str>> = Mutex::new(None);
+
+static TOTAL: AtomicUsize = AtomicUsize::new(0);
+static PASSED: AtomicUsize = AtomicUsize::new(0);
+static FAILED: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns every test registered in the program.
+#[allow(clippy::missing_const_for_fn)]
+fn tests() -> &'static [Test] {
+    #[cfg(target_vendor = "vex")]
+    {
+        // These symbols are defined in vexide's linkerscript (link/v5.ld).
+        unsafe extern "C" {
+            static __vexide_tests_start: Test;
+            static __vexide_tests_end: Test;
+        }
+
+        let start = &raw const __vexide_tests_start;
+        let end = &raw const __vexide_tests_end;
+
+        unsafe {
+            core::slice::from_raw_parts(start, (end as usize - start as usize) / size_of::<Test>())
+        }
+    }
+
+    #[cfg(not(target_vendor = "vex"))]
+    &[]
+}
+
+/// Prints the final line of the test report.
+fn print_summary() {
+    let total = TOTAL.load(Ordering::Relaxed);
+    let passed = PASSED.load(Ordering::Relaxed);
+    let failed = FAILED.load(Ordering::Relaxed);
+
+    println!(
+        "test result: {}. {passed} passed; {failed} failed; {} not run",
+        if failed == 0 { "ok" } else { "FAILED" },
+        total - passed - failed,
+    );
+}
+
+/// Runs every test in the program and prints the results to stdout.
+///
+/// This is called by the entrypoint created with `vexide::test_main!()`.
+pub async fn run() {
+    let mut tests = tests().to_vec();
+    tests.sort_unstable_by_key(|test| test.name);
+
+    TOTAL.store(tests.len(), Ordering::Relaxed);
+    println!(
+        "running {} test{}",
+        tests.len(),
+        if tests.len() == 1 { "" } else { "s" }
+    );
+
+    for test in tests {
+        if let Some(mut current) = CURRENT_TEST.try_lock() {
+            *current = Some(test.name);
+        }
+
+        let timeout = test.timeout.unwrap_or(DEFAULT_TIMEOUT);
+        let deadline = Instant::now() + timeout;
+        let mut future = (test.run)();
+
+        let finished = poll_fn(|cx| {
+            if future.as_mut().poll(cx).is_ready() {
+                return Poll::Ready(true);
+            }
+            if Instant::now() >= deadline {
+                return Poll::Ready(false);
+            }
+
+            // Make sure the test is polled again so that the deadline gets checked, even if
+            // the test is waiting on something that won't wake it up.
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        })
+        .await;
+
+        if finished {
+            PASSED.fetch_add(1, Ordering::Relaxed);
+            println!("test {} ... ok", test.name);
+        } else {
+            FAILED.fetch_add(1, Ordering::Relaxed);
+            println!(
+                "test {} ... FAILED (timed out after {timeout:?})",
+                test.name
+            );
+        }
+    }
+
+    if let Some(mut current) = CURRENT_TEST.try_lock() {
+        *current = None;
+    }
+
+    print_summary();
+}
+
+/// Reports the running test as failed, then exits the program.
+///
+/// This is installed as the panic hook by the entrypoint created with `vexide::test_main!()`.
+pub fn panic_hook(info: &PanicInfo<'_>) {
+    let current = CURRENT_TEST.try_lock().and_then(|current| *current);
+    let message = info.message().to_string().replace('\n', "\\n");
+
+    match (current, info.location()) {
+        (Some(name), Some(location)) => {
+            FAILED.fetch_add(1, Ordering::Relaxed);
+            println!("test {name} ... FAILED (panicked at {location}: {message})");
+        }
+        (Some(name), None) => {
+            FAILED.fetch_add(1, Ordering::Relaxed);
+            println!("test {name} ... FAILED (panicked: {message})");
+        }
+        (None, _) => println!("test harness panicked: {message}"),
+    }
+
+    print_summary();
+    vexide_core::program::exit();
+}
@@ -0,0 +1,486 @@
+//! Symbol table generation from linked ELF files.
+
+use alloc::{format, string::String, vec::Vec};
+
+use rustc_demangle::demangle;
+use snafu::{ensure, OptionExt, Snafu};
+
+use crate::{ENTRY_SIZE, HEADER_SIZE, MAGIC, SECTION_NAME};
+
+/// The longest function name that will be stored in a symbol table.
+///
+/// Longer names (typically functions with many generic parameters) are truncated.
+const MAX_NAME_LEN: usize = 128;
+
+const SHT_SYMTAB: u32 = 2;
+const STT_FUNC: u8 = 2;
+const SECTION_HEADER_SIZE: usize = 40;
+const SYMBOL_SIZE: usize = 16;
+
+/// Errors that can occur when generating or embedding a symbol table.
+#[derive(Debug, Snafu)]
+pub enum GenerateError {
+    /// The input is not a 32-bit little-endian ELF file.
+    NotElf,
+    /// The ELF file's headers point outside of the file.
+    Malformed,
+    /// The ELF file has no symbol table (it may have been stripped).
+    MissingSymbols,
+    /// The ELF file has no section to embed the symbol table in.
+    #[snafu(display(
+        "The ELF file has no `{SECTION_NAME}` section. Is the `symbolication` feature enabled?"
+    ))]
+    MissingSection,
+    /// The generated symbol table does not fit in the ELF file's reserved section.
+    #[snafu(display(
+        "The symbol table ({size} bytes) does not fit in the `{SECTION_NAME}` section ({capacity} bytes)."
+    ))]
+    TableTooLarge {
+        /// The size of the generated table.
+        size: usize,
+        /// The size of the reserved section.
+        capacity: usize,
+    },
+}
+
+/// A section header from an ELF file.
+struct Section {
+    name: u32,
+    kind: u32,
+    offset: usize,
+    size: usize,
+    link: u32,
+}
+
+/// A function symbol from an ELF file.
+struct Function<'a> {
+    address: u32,
+    size: u32,
+    /// The function's mangled name.
+    name: &'a [u8],
+}
+
+/// A minimal reader for 32-bit little-endian ELF files.
+struct Elf<'a> {
+    data: &'a [u8],
+    sections: Vec<Section>,
+    section_names: usize,
+}
+
+impl<'a> Elf<'a> {
+    fn parse(data: &'a [u8]) -> Result<Self, GenerateError> {
+        // Magic, 32-bit class, little-endian encoding.
+        ensure!(data.get(0..6) == Some(b"\x7fELF\x01\x01"), NotElfSnafu);
+
+        let section_offset = read_u32(data, 0x20)? as usize;
+        let section_count = usize::from(read_u16(data, 0x30)?);
+        let section_names = usize::from(read_u16(data, 0x32)?);
+
+        let sections = (0..section_count)
+            .map(|index| {
+                let header = section_offset + index * SECTION_HEADER_SIZE;
+                Ok(Section {
+                    name: read_u32(data, header)?,
+                    kind: read_u32(data, header + 4)?,
+                    offset: read_u32(data, header + 16)? as usize,
+                    size: read_u32(data, header + 20)? as usize,
+                    link: read_u32(data, header + 24)?,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        ensure!(section_names < sections.len(), MalformedSnafu);
+
+        Ok(Self {
+            data,
+            sections,
+            section_names,
+        })
+    }
+
+    fn section_data(&self, section: &Section) -> Result<&'a [u8], GenerateError> {
+        self.data
+            .get(section.offset..section.offset + section.size)
+            .context(MalformedSnafu)
+    }
+
+    fn section_name(&self, section: &Section) -> Result<&'a [u8], GenerateError> {
+        let names = self.section_data(&self.sections[self.section_names])?;
+        read_str(names, section.name as usize)
+    }
+
+    fn find_section(&self, name: &str) -> Result<Option<&Section>, GenerateError> {
+        for section in &self.sections {
+            if self.section_name(section)? == name.as_bytes() {
+                return Ok(Some(section));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Returns each function in the ELF's symbol table.
+    fn functions(&self) -> Result<Vec<Function<'a>>, GenerateError> {
+        let symtab = self
+            .sections
+            .iter()
+            .find(|section| section.kind == SHT_SYMTAB)
+            .context(MissingSymbolsSnafu)?;
+        let strtab = self
+            .sections
+            .get(symtab.link as usize)
+            .context(MalformedSnafu)?;
+
+        let symbols = self.section_data(symtab)?;
+        let strings = self.section_data(strtab)?;
+
+        let mut functions = Vec::new();
+        for symbol in symbols.chunks_exact(SYMBOL_SIZE) {
+            let name = read_u32(symbol, 0)?;
+            let value = read_u32(symbol, 4)?;
+            let size = read_u32(symbol, 8)?;
+            let info = symbol[12];
+
+            if info & 0xf != STT_FUNC || size == 0 {
+                continue;
+            }
+
+            // The lowest bit of a function's address is set for Thumb functions.
+            functions.push(Function {
+                address: value & !1,
+                size,
+                name: read_str(strings, name as usize)?,
+            });
+        }
+
+        Ok(functions)
+    }
+}
+
+/// Generates a symbol table from the function symbols in a linked ELF file.
+///
+/// Function names are demangled and stored without their hashes (for example,
+/// `my_program::main::{{closure}}`).
+///
+/// # Errors
+///
+/// - A [`GenerateError::NotElf`] or [`GenerateError::Malformed`] error is returned if `elf`
+///   is not a valid 32-bit little-endian ELF file.
+/// - A [`GenerateError::MissingSymbols`] error is returned if the ELF file has been stripped.
+pub fn generate(elf: &[u8]) -> Result<Vec<u8>, GenerateError> {
+    let elf = Elf::parse(elf)?;
+
+    let mut functions = elf.functions()?;
+    functions.sort_unstable_by_key(|function| function.address);
+    // Aliases of the same function would never be looked up.
+    functions.dedup_by_key(|function| function.address);
+
+    let mut entries = Vec::with_capacity(functions.len() * ENTRY_SIZE);
+    let mut names = Vec::new();
+
+    for function in functions {
+        let name = String::from_utf8_lossy(function.name);
+        let mut name = format!("{:#}", demangle(&name));
+        if name.len() > MAX_NAME_LEN {
+            let mut end = MAX_NAME_LEN;
+            while !name.is_char_boundary(end) {
+                end -= 1;
+            }
+            name.truncate(end);
+        }
+
+        for field in [
+            function.address,
+            function.size,
+            names.len() as u32,
+            name.len() as u32,
+        ] {
+            entries.extend_from_slice(&field.to_le_bytes());
+        }
+        names.extend_from_slice(name.as_bytes());
+    }
+
+    let mut table = Vec::with_capacity(HEADER_SIZE + entries.len() + names.len());
+    table.extend_from_slice(&MAGIC);
+    table.extend_from_slice(&((entries.len() / ENTRY_SIZE) as u32).to_le_bytes());
+    table.extend_from_slice(&entries);
+    table.extend_from_slice(&names);
+
+    Ok(table)
+}
+
+/// Generates a symbol table from a linked ELF file and writes it into the ELF's
+/// [`SECTION_NAME`] section.
+///
+/// This should be run on the final ELF file of a program after linking, but before it is
+/// converted to a binary for uploading. Any unused space in the section is zeroed.
+///
+/// Returns the size of the generated table in bytes.
+///
+/// # Errors
+///
+/// In addition to the errors returned by [`generate`]:
+///
+/// - A [`GenerateError::MissingSection`] error is returned if the program was built without
+///   the `symbolication` feature.
+/// - A [`GenerateError::TableTooLarge`] error is returned if the generated table does not fit
+///   in the reserved section.
+pub fn embed(elf: &mut [u8]) -> Result<usize, GenerateError> {
+    let table = generate(elf)?;
+
+    let parsed = Elf::parse(elf)?;
+    let section = parsed
+        .find_section(SECTION_NAME)?
+        .context(MissingSectionSnafu)?;
+    let (offset, capacity) = (section.offset, section.size);
+
+    ensure!(
+        table.len() <= capacity,
+        TableTooLargeSnafu {
+            size: table.len(),
+            capacity,
+        }
+    );
+
+    let section = &mut elf[offset..offset + capacity];
+    section[..table.len()].copy_from_slice(&table);
+    section[table.len()..].fill(0);
+
+    Ok(table.len())
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, GenerateError> {
+    Ok(u16::from_le_bytes(
+        data.get(offset..offset + 2)
+            .context(MalformedSnafu)?
+            .try_into()
+            .unwrap(),
+    ))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, GenerateError> {
+    Ok(u32::from_le_bytes(
+        data.get(offset..offset + 4)
+            .context(MalformedSnafu)?
+            .try_into()
+            .unwrap(),
+    ))
+}
+
+/// Reads a NUL-terminated string from an ELF string table.
+fn read_str(data: &[u8], offset: usize) -> Result<&[u8], GenerateError> {
+    let data = data.get(offset..).context(MalformedSnafu)?;
+    let len = data
+        .iter()
+        .position(|&byte| byte == 0)
+        .context(MalformedSnafu)?;
+    Ok(&data[..len])
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{vec, vec::Vec};
+
+    use super::*;
+    use crate::SymbolTable;
+
+    const STT_OBJECT: u8 = 1;
+
+    /// Builds a minimal ELF file containing the given `(address, size, kind, name)` symbols and
+    /// a [`SECTION_NAME`] section of `capacity` bytes.
+    fn elf(symbols: &[(u32, u32, u8, &str)], capacity: usize) -> Vec<u8> {
+        fn push_u32(data: &mut Vec<u8>, value: u32) {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+
+        let section_names = b"\0.shstrtab\0.strtab\0.symtab\0.vexide_symbols\0";
+
+        let mut strings = vec![0];
+        let mut symtab = vec![0; SYMBOL_SIZE];
+        for &(address, size, kind, name) in symbols {
+            push_u32(&mut symtab, strings.len() as u32);
+            push_u32(&mut symtab, address);
+            push_u32(&mut symtab, size);
+            symtab.extend_from_slice(&[kind, 0, 1, 0]);
+            strings.extend_from_slice(name.as_bytes());
+            strings.push(0);
+        }
+
+        let mut data = vec![0; 0x34];
+        data[0..7].copy_from_slice(b"\x7fELF\x01\x01\x01");
+
+        // (name offset, type, contents, link)
+        let sections: [(u32, u32, &[u8], u32); 5] = [
+            (0, 0, &[], 0),
+            (1, 3, section_names, 0),
+            (11, 3, &strings, 0),
+            (19, SHT_SYMTAB, &symtab, 2),
+            (27, 1, &vec![0xAA; capacity], 0),
+        ];
+
+        let mut offsets = Vec::new();
+        for (_, _, contents, _) in sections {
+            offsets.push(data.len() as u32);
+            data.extend_from_slice(contents);
+        }
+
+        let section_headers = data.len() as u32;
+        for ((name, kind, contents, link), offset) in sections.into_iter().zip(offsets) {
+            for field in [
+                name,
+                kind,
+                0,
+                0,
+                offset,
+                contents.len() as u32,
+                link,
+                0,
+                0,
+                0,
+            ] {
+                push_u32(&mut data, field);
+            }
+        }
+
+        data[0x20..0x24].copy_from_slice(&section_headers.to_le_bytes());
+        data[0x30..0x32].copy_from_slice(&(sections.len() as u16).to_le_bytes());
+        data[0x32..0x34].copy_from_slice(&1u16.to_le_bytes());
+
+        data
+    }
+
+    #[test]
+    fn sorts_by_address() {
+        let table = generate(&elf(
+            &[
+                (0x300, 0x10, STT_FUNC, "third"),
+                (0x100, 0x10, STT_FUNC, "first"),
+                (0x200, 0x10, STT_FUNC, "second"),
+            ],
+            0,
+        ))
+        .unwrap();
+        let table = SymbolTable::parse(&table).unwrap();
+
+        assert_eq!(table.len(), 3);
+        assert_eq!(table.lookup(0x100), Some("first"));
+        assert_eq!(table.lookup(0x208), Some("second"));
+        assert_eq!(table.lookup(0x30f), Some("third"));
+    }
+
+    #[test]
+    fn skips_empty_and_non_function_symbols() {
+        let table = generate(&elf(
+            &[
+                (0x100, 0x10, STT_FUNC, "function"),
+                (0x200, 0, STT_FUNC, "empty"),
+                (0x300, 0x10, STT_OBJECT, "data"),
+            ],
+            0,
+        ))
+        .unwrap();
+        let table = SymbolTable::parse(&table).unwrap();
+
+        assert_eq!(table.len(), 1);
+        assert_eq!(table.lookup(0x200), None);
+        assert_eq!(table.lookup(0x300), None);
+    }
+
+    #[test]
+    fn demangles_and_clears_thumb_bit() {
+        let table = generate(&elf(
+            &[(
+                0x101,
+                0x10,
+                STT_FUNC,
+                "_ZN7program5robot5drive17h24493cbf6c3f10bcE",
+            )],
+            0,
+        ))
+        .unwrap();
+        let table = SymbolTable::parse(&table).unwrap();
+
+        assert_eq!(table.lookup(0x100), Some("program::robot::drive"));
+    }
+
+    #[test]
+    fn removes_aliases() {
+        let table = generate(&elf(
+            &[
+                (0x100, 0x10, STT_FUNC, "alias"),
+                (0x100, 0x10, STT_FUNC, "alias"),
+            ],
+            0,
+        ))
+        .unwrap();
+
+        assert_eq!(SymbolTable::parse(&table).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn truncates_long_names() {
+        let name = "a".repeat(MAX_NAME_LEN + 10);
+        let table = generate(&elf(&[(0x100, 0x10, STT_FUNC, &name)], 0)).unwrap();
+        let table = SymbolTable::parse(&table).unwrap();
+
+        assert_eq!(table.lookup(0x100), Some(&name[..MAX_NAME_LEN]));
+    }
+
+    #[test]
+    fn embeds_and_zeroes_unused_space() {
+        let mut data = elf(&[(0x100, 0x10, STT_FUNC, "function")], 256);
+        let size = embed(&mut data).unwrap();
+        assert_eq!(size, HEADER_SIZE + ENTRY_SIZE + "function".len());
+
+        let section = Elf::parse(&data)
+            .unwrap()
+            .find_section(SECTION_NAME)
+            .unwrap()
+            .map(|section| (section.offset, section.size))
+            .unwrap();
+        let section = &data[section.0..section.0 + section.1];
+
+        assert_eq!(
+            SymbolTable::parse(section).unwrap().lookup(0x100),
+            Some("function")
+        );
+        assert!(section[size..].iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn rejects_table_too_large() {
+        let mut data = elf(&[(0x100, 0x10, STT_FUNC, "function")], 16);
+
+        assert!(matches!(
+            embed(&mut data),
+            Err(GenerateError::TableTooLarge {
+                size: 32,
+                capacity: 16
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_missing_section() {
+        let mut data = elf(&[(0x100, 0x10, STT_FUNC, "function")], 0);
+        // Rename the reserved section.
+        let name = data
+            .windows(16)
+            .position(|w| w == b".vexide_symbols\0")
+            .unwrap();
+        data[name + 1] = b'X';
+
+        assert!(matches!(
+            embed(&mut data),
+            Err(GenerateError::MissingSection)
+        ));
+    }
+
+    #[test]
+    fn rejects_non_elf() {
+        assert!(matches!(
+            generate(b"not an elf"),
+            Err(GenerateError::NotElf)
+        ));
+    }
+}
@@ -0,0 +1,242 @@
+//! Compact function name tables for on-device symbolication of [`vexide`](https://crates.io/crates/vexide) backtraces.
+//!
+//! Backtraces captured on a V5 Brain are lists of raw instruction pointers, which normally
+//! have to be turned into function names on a computer using a tool like `llvm-symbolizer`.
+//! This crate defines a small table mapping address ranges to (demangled) function names
+//! that can be embedded into a program binary, allowing the brain to do that itself.
+//!
+//! Included in this crate:
+//! - Reading symbol tables on the brain: [`SymbolTable`]
+//! - Generating symbol tables from a linked ELF file: [`generate`] and [`embed`] (requires
+//!   the `generate` feature)
+//!
+//! # How it works
+//!
+//! When the `symbolication` feature of `vexide` is enabled, space for the table is reserved
+//! in a section named [`SECTION_NAME`]. After the program is linked, [`embed`] is called on
+//! the final ELF file, which reads the ELF's symbol table and writes the function name table
+//! into the reserved section before the ELF is converted to a binary for uploading. Since the
+//! reserved section has a fixed size, filling it in does not move any code or data in the
+//! program.
+//!
+//! # Embedding symbols
+//!
+//! With the `generate` feature enabled, this crate provides a `vexide-symbols` command that
+//! embeds a table into an ELF file in place:
+//!
+//! ```sh
+//! cargo install vexide-symbols --features generate
+//! vexide-symbols target/armv7a-vex-v5/release/my-program
+//! ```
+//!
+//! It can also be run as a Cargo runner in front of `cargo-v5`, so that `cargo run` embeds the
+//! table before every upload:
+//!
+//! ```toml
+//! # .cargo/config.toml
+//! [target.'cfg(target_vendor = "vex")']
+//! runner = "vexide-symbols --then cargo v5 run --file"
+//! ```
+//!
+//! # Format
+//!
+//! All integers are stored as little-endian `u32`s.
+//!
+//! | Offset               | Contents                                               |
+//! |----------------------|--------------------------------------------------------|
+//! | `0`                  | [`MAGIC`]                                              |
+//! | `4`                  | Number of entries (`n`)                                |
+//! | `8`                  | `n` entries of [`ENTRY_SIZE`] bytes, sorted by address |
+//! | `8 + n * ENTRY_SIZE` | UTF-8 function names                                   |
+//!
+//! Each entry contains a function's start address, its size in bytes, and the offset and
+//! length of its name relative to the start of the name data.
+
+#![no_std]
+
+#[cfg(feature = "generate")]
+extern crate alloc;
+
+#[cfg(feature = "generate")]
+mod generate;
+
+#[cfg(feature = "generate")]
+pub use generate::{embed, generate, GenerateError};
+
+/// The name of the linker section that symbol tables are embedded in.
+pub const SECTION_NAME: &str = ".vexide_symbols";
+
+/// Bytes identifying the start of a symbol table.
+pub const MAGIC: [u8; 4] = *b"VSYM";
+
+/// The size of a symbol table's header in bytes.
+pub const HEADER_SIZE: usize = 8;
+
+/// The size of a single entry in a symbol table in bytes.
+pub const ENTRY_SIZE: usize = 16;
+
+/// A function name table embedded in a program.
+///
+/// # Examples
+///
+/// ```
+/// use vexide_symbols::SymbolTable;
+///
+/// // An empty table (as reserved by vexide before it is filled in).
+/// let data = [b'V', b'S', b'Y', b'M', 0, 0, 0, 0];
+/// let table = SymbolTable::parse(&data).unwrap();
+///
+/// assert!(table.is_empty());
+/// assert_eq!(table.lookup(0x0380_0000), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SymbolTable<'a> {
+    entries: &'a [u8],
+    names: &'a [u8],
+}
+
+impl<'a> SymbolTable<'a> {
+    /// Parses a symbol table from the start of `data`.
+    ///
+    /// Returns `None` if `data` does not start with a valid symbol table. Any data following
+    /// the table (such as unused space in the section it was embedded in) is ignored.
+    #[must_use]
+    pub fn parse(data: &'a [u8]) -> Option<Self> {
+        if data.get(0..4)? != MAGIC {
+            return None;
+        }
+
+        let len = read_u32(data, 4)? as usize;
+        let entries_end = HEADER_SIZE.checked_add(len.checked_mul(ENTRY_SIZE)?)?;
+
+        Some(Self {
+            entries: data.get(HEADER_SIZE..entries_end)?,
+            names: &data[entries_end..],
+        })
+    }
+
+    /// Returns the number of functions in the table.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.entries.len() / ENTRY_SIZE
+    }
+
+    /// Returns `true` if the table contains no functions.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the name of the function containing `address`, if there is one in the table.
+    #[must_use]
+    pub fn lookup(&self, address: u32) -> Option<&'a str> {
+        let entry = |index: usize| -> Option<[u32; 4]> {
+            let offset = index * ENTRY_SIZE;
+            Some([
+                read_u32(self.entries, offset)?,
+                read_u32(self.entries, offset + 4)?,
+                read_u32(self.entries, offset + 8)?,
+                read_u32(self.entries, offset + 12)?,
+            ])
+        };
+
+        // Find the last function starting at or before the address.
+        let (mut low, mut high) = (0, self.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if entry(mid)?[0] <= address {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+
+        let [start, size, name_offset, name_len] = entry(low.checked_sub(1)?)?;
+        if address - start >= size {
+            return None;
+        }
+
+        let name_start = name_offset as usize;
+        let name = self
+            .names
+            .get(name_start..name_start.checked_add(name_len as usize)?)?;
+
+        core::str::from_utf8(name).ok()
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        data.get(offset..offset.checked_add(4)?)?.try_into().ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Builds a table from `(address, size, name)` entries, which must be sorted by address.
+    fn table(functions: &[(u32, u32, &str)]) -> ([u8; 256], usize) {
+        let mut data = [0; 256];
+        data[0..4].copy_from_slice(&MAGIC);
+        data[4..8].copy_from_slice(&(functions.len() as u32).to_le_bytes());
+
+        let names_start = HEADER_SIZE + functions.len() * ENTRY_SIZE;
+        let mut names_len = 0;
+        for (i, (address, size, name)) in functions.iter().enumerate() {
+            let entry = HEADER_SIZE + i * ENTRY_SIZE;
+            for (j, field) in [*address, *size, names_len as u32, name.len() as u32]
+                .into_iter()
+                .enumerate()
+            {
+                data[entry + j * 4..entry + j * 4 + 4].copy_from_slice(&field.to_le_bytes());
+            }
+
+            let start = names_start + names_len;
+            data[start..start + name.len()].copy_from_slice(name.as_bytes());
+            names_len += name.len();
+        }
+
+        (data, names_start + names_len)
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        assert_eq!(SymbolTable::parse(b"ELF\0\0\0\0\0"), None);
+        assert_eq!(SymbolTable::parse(b"VSY"), None);
+    }
+
+    #[test]
+    fn rejects_truncated_entries() {
+        let (data, _) = table(&[(0x100, 0x10, "a"), (0x200, 0x10, "b")]);
+        assert_eq!(SymbolTable::parse(&data[..HEADER_SIZE + ENTRY_SIZE]), None);
+    }
+
+    #[test]
+    fn looks_up_containing_function() {
+        let (data, len) = table(&[
+            (0x100, 0x10, "first"),
+            (0x110, 0x20, "second::inner"),
+            (0x200, 0x4, "third"),
+        ]);
+        let table = SymbolTable::parse(&data[..len]).unwrap();
+
+        assert_eq!(table.len(), 3);
+        assert_eq!(table.lookup(0x100), Some("first"));
+        assert_eq!(table.lookup(0x10f), Some("first"));
+        assert_eq!(table.lookup(0x110), Some("second::inner"));
+        assert_eq!(table.lookup(0x12f), Some("second::inner"));
+        assert_eq!(table.lookup(0x203), Some("third"));
+    }
+
+    #[test]
+    fn ignores_addresses_outside_functions() {
+        let (data, len) = table(&[(0x100, 0x10, "first"), (0x200, 0x4, "second")]);
+        let table = SymbolTable::parse(&data[..len]).unwrap();
+
+        assert_eq!(table.lookup(0x0), None);
+        assert_eq!(table.lookup(0xff), None);
+        assert_eq!(table.lookup(0x110), None);
+        assert_eq!(table.lookup(0x204), None);
+    }
+}
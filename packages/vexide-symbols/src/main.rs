@@ -0,0 +1,77 @@
+//! Embeds a function name table into a linked vexide program.
+//!
+//! ```text
+//! vexide-symbols <ELF>
+//! vexide-symbols --then <COMMAND>... <ELF>
+//! ```
+//!
+//! The table is written into the ELF file in place. With `--then`, the given command is run
+//! afterwards with the ELF file appended to its arguments, which allows this tool to be used
+//! as a Cargo runner in front of `cargo-v5`:
+//!
+//! ```toml
+//! # .cargo/config.toml
+//! [target.'cfg(target_vendor = "vex")']
+//! runner = "vexide-symbols --then cargo v5 run --file"
+//! ```
+
+use std::{
+    env, fs,
+    process::{self, Command, ExitCode},
+};
+
+const USAGE: &str = "usage: vexide-symbols [--then <COMMAND>...] <ELF>";
+
+fn main() -> ExitCode {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+
+    let then = args.first().is_some_and(|arg| arg == "--then");
+    if then {
+        args.remove(0);
+    }
+
+    let Some(path) = args.pop() else {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    };
+    if (then && args.is_empty()) || (!then && !args.is_empty()) {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    }
+
+    let mut elf = match fs::read(&path) {
+        Ok(elf) => elf,
+        Err(error) => {
+            eprintln!("error: failed to read `{path}`: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let size = match vexide_symbols::embed(&mut elf) {
+        Ok(size) => size,
+        Err(error) => {
+            eprintln!("error: failed to embed symbols into `{path}`: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if let Err(error) = fs::write(&path, &elf) {
+        eprintln!("error: failed to write `{path}`: {error}");
+        return ExitCode::FAILURE;
+    }
+    eprintln!("Embedded {size} bytes of function names into `{path}`");
+
+    if then {
+        let status = match Command::new(&args[0]).args(&args[1..]).arg(&path).status() {
+            Ok(status) => status,
+            Err(error) => {
+                eprintln!("error: failed to run `{}`: {error}", args[0]);
+                return ExitCode::FAILURE;
+            }
+        };
+
+        process::exit(status.code().unwrap_or(1));
+    }
+
+    ExitCode::SUCCESS
+}
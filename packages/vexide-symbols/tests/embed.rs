@@ -0,0 +1,93 @@
+//! Tests embedding a symbol table into a real ELF file.
+//!
+//! `fixtures/program.elf` is built from `fixtures/program.rs`. See that file for how to
+//! rebuild it.
+
+use std::{fs, process::Command};
+
+use vexide_symbols::{embed, GenerateError, SymbolTable, MAGIC};
+
+const PROGRAM: &[u8] = include_bytes!("fixtures/program.elf");
+
+/// The file offset and size of the fixture's `.vexide_symbols` section (from `readelf -S`).
+const SECTION_OFFSET: usize = 0x10c;
+const SECTION_SIZE: usize = 0x1000;
+
+fn check_table(elf: &[u8]) {
+    let section = &elf[SECTION_OFFSET..SECTION_OFFSET + SECTION_SIZE];
+    let table = SymbolTable::parse(section).expect("section should contain a symbol table");
+
+    assert_eq!(table.len(), 3);
+    assert_eq!(table.lookup(0x00), Some("program::robot::drive"));
+    assert_eq!(table.lookup(0x1b), Some("program::robot::drive"));
+    assert_eq!(table.lookup(0x1c), Some("program::robot::intake"));
+    assert_eq!(table.lookup(0x38), Some("_start"));
+    assert_eq!(table.lookup(0x48), None);
+    // Data symbols aren't included.
+    assert_eq!(table.lookup(0x58), None);
+}
+
+#[test]
+fn embeds_into_reserved_section() {
+    let mut elf = PROGRAM.to_vec();
+    let size = embed(&mut elf).unwrap();
+
+    assert_eq!(elf[SECTION_OFFSET..SECTION_OFFSET + 4], MAGIC);
+    assert!(elf[SECTION_OFFSET + size..SECTION_OFFSET + SECTION_SIZE]
+        .iter()
+        .all(|&byte| byte == 0));
+    check_table(&elf);
+
+    // Nothing outside of the section is modified.
+    assert_eq!(elf[..SECTION_OFFSET], PROGRAM[..SECTION_OFFSET]);
+    assert_eq!(
+        elf[SECTION_OFFSET + SECTION_SIZE..],
+        PROGRAM[SECTION_OFFSET + SECTION_SIZE..]
+    );
+}
+
+#[test]
+fn embedding_twice_is_idempotent() {
+    let mut once = PROGRAM.to_vec();
+    embed(&mut once).unwrap();
+
+    let mut twice = once.clone();
+    embed(&mut twice).unwrap();
+
+    assert_eq!(once, twice);
+}
+
+#[test]
+fn rejects_stripped_program() {
+    let mut elf = PROGRAM.to_vec();
+    // Turn the symbol table into an unused section type.
+    let section_headers = u32::from_le_bytes(elf[0x20..0x24].try_into().unwrap()) as usize;
+    let section_count = u16::from_le_bytes(elf[0x30..0x32].try_into().unwrap()) as usize;
+    for index in 0..section_count {
+        let kind = section_headers + index * 40 + 4;
+        if elf[kind..kind + 4] == 2u32.to_le_bytes() {
+            elf[kind..kind + 4].copy_from_slice(&0u32.to_le_bytes());
+        }
+    }
+
+    assert!(matches!(
+        embed(&mut elf),
+        Err(GenerateError::MissingSymbols)
+    ));
+}
+
+#[test]
+fn command_line_tool() {
+    let path = std::env::temp_dir().join(format!("vexide-symbols-{}.elf", std::process::id()));
+    fs::write(&path, PROGRAM).unwrap();
+
+    let status = Command::new(env!("CARGO_BIN_EXE_vexide-symbols"))
+        .arg(&path)
+        .status()
+        .unwrap();
+    let elf = fs::read(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert!(status.success());
+    check_table(&elf);
+}
@@ -0,0 +1,41 @@
+//! A tiny program used to test embedding symbol tables into a real ELF file.
+//!
+//! Rebuild `program.elf` with:
+//!
+//! ```sh
+//! rustc --target armv7a-none-eabi -C opt-level=1 -C panic=abort -C strip=none \
+//!     -C link-arg=-Tlink.ld -C link-arg=-n program.rs -o program.elf
+//! ```
+
+#![no_std]
+#![no_main]
+
+/// Space reserved for the symbol table, as vexide does when `symbolication` is enabled.
+#[used]
+#[link_section = ".vexide_symbols"]
+static SYMBOLS: [u8; 4096] = [0; 4096];
+
+mod robot {
+    #[inline(never)]
+    pub fn drive(speed: u32) -> u32 {
+        core::hint::black_box(speed) * 2
+    }
+
+    #[inline(never)]
+    pub fn intake(speed: u32) -> u32 {
+        core::hint::black_box(speed) + 1
+    }
+}
+
+#[no_mangle]
+extern "C" fn _start() -> ! {
+    let mut speed = 0;
+    loop {
+        speed = robot::intake(robot::drive(speed));
+    }
+}
+
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo<'_>) -> ! {
+    loop {}
+}
@@ -68,15 +68,17 @@ pub mod time {
 #[doc(inline)]
 #[cfg(feature = "allocator")]
 pub use vexide_core::allocator;
+#[cfg(feature = "crash_log")]
+pub use vexide_core::crash_log;
 #[doc(inline)]
 #[cfg(feature = "core")]
-pub use vexide_core::{backtrace, competition, float, fs, io, os, path, program, sync};
+pub use vexide_core::{backtrace, competition, float, fs, io, os, path, program, stack, sync};
 #[doc(inline)]
 #[cfg(feature = "devices")]
 pub use vexide_devices as devices;
 #[doc(inline)]
 #[cfg(feature = "macro")]
-pub use vexide_macro::main;
+pub use vexide_macro::{main, test, test_main};
 #[doc(inline)]
 #[cfg(feature = "panic")]
 pub use vexide_panic as panic;
@@ -109,6 +111,7 @@ pub mod prelude {
         battery,
         controller::Controller,
         display::Display,
+        drive::DifferentialDrive,
         peripherals::{DynamicPeripherals, Peripherals},
         position::Position,
         rgb::Rgb,
@@ -118,7 +121,7 @@ pub mod prelude {
             expander::AdiExpander,
             imu::InertialSensor,
             link::{LinkType, RadioLink},
-            motor::{BrakeMode, Direction, Gearset, Motor, MotorControl},
+            motor::{BrakeMode, Direction, Gearset, Motor, MotorControl, MotorGroup},
             optical::OpticalSensor,
             rotation::RotationSensor,
             serial::SerialPort,